@@ -1,8 +1,38 @@
 import test from 'ava'
-import { crawl, Website, Page, type NPage, Cron, pageTitle } from '../index.js'
+import { createServer, type RequestListener } from 'node:http'
+import type { AddressInfo } from 'node:net'
+import {
+  crawl,
+  Website,
+  Page,
+  BrowserPool,
+  Robots,
+  CrawlPool,
+  type NPage,
+  type NWebsiteConfig,
+  type NCrawlStats,
+  type NLogRecord,
+  type NCookie,
+  Cron,
+  pageTitle,
+  setLogger,
+} from '../index.js'
 
 const TEST_URL = 'https://choosealicense.com'
 
+/** run the test against a local server closed once done. */
+const withServer = async <T>(handler: RequestListener, fn: (url: string) => Promise<T>): Promise<T> => {
+  const server = createServer(handler)
+
+  await new Promise<void>((resolve) => server.listen(0, '127.0.0.1', resolve))
+
+  try {
+    return await fn(`http://127.0.0.1:${(server.address() as AddressInfo).port}`)
+  } finally {
+    server.close()
+  }
+}
+
 test('shortcut crawl native', async (t) => {
   const { links, pages } = await crawl(TEST_URL)
 
@@ -48,6 +78,50 @@ test('new website native budget one page', async (t) => {
   t.assert(website.getLinks().length === 1, 'should be one link')
 })
 
+test('new website native limit', async (t) => {
+  const website = new Website(TEST_URL).withLimit(2).build()
+
+  await website.crawl()
+
+  t.assert(website.getLinks().length <= 2, 'should be at most two links')
+  t.is(website.status, 'LimitReached')
+})
+
+test('new website native limit keeps the budget', async (t) => {
+  const website = new Website(TEST_URL).withBudget({ '*': 5, '/blog': 1 }).withLimit(2)
+
+  t.deepEqual(website.getConfig().budget, { '*': 5, '/blog': 1 })
+
+  website.withLimit(0)
+
+  t.deepEqual(website.getConfig().budget, { '*': 5, '/blog': 1 })
+})
+
+test('new website native limit with every page crawled', async (t) => {
+  await withServer(
+    (req, res) => {
+      const body = req.url === '/' ? '<a href="/last">last</a>' : 'last'
+
+      res.writeHead(200, { 'Content-Type': 'text/html' }).end(`<html><body>${body}</body></html>`)
+    },
+    async (url) => {
+      const website = new Website(url).withLimit(2)
+
+      await website.crawl()
+
+      t.is(website.status === 'LimitReached', website.getLinks().length < 2)
+    },
+  )
+})
+
+test('new website native crawl timeout', async (t) => {
+  const website = new Website(TEST_URL).withCrawlTimeout(1).build()
+
+  await website.crawl()
+
+  t.is(website.status, 'TimedOut')
+})
+
 test('new website native blacklist pages', async (t) => {
   const website = new Website(TEST_URL).withBlacklistUrl(['/blog', new RegExp('/books').source, '/resume']).build()
 
@@ -138,6 +212,709 @@ test('new website native with subscriptions', async (t) => {
   t.assert(links.length > 1, 'should be more than one page')
 })
 
+test('new website stats and progress', async (t) => {
+  const progress: NCrawlStats[] = []
+
+  const website = new Website(TEST_URL).withOnProgress((_err: Error | null, stats: NCrawlStats) => {
+    progress.push(stats)
+  }, 100)
+
+  await website.crawl()
+
+  const stats = website.stats()
+
+  t.assert(stats.pagesFetched > 1, 'should be more than one page fetched')
+  t.assert(stats.bytesDownloaded > 0, 'should download bytes')
+  t.is(stats.pagesQueued, 0)
+  t.assert(progress.length >= 1, 'should send progress events')
+})
+
+test('new website metrics endpoint', async (t) => {
+  const http = await import('node:http')
+  const website = new Website(TEST_URL).withLimit(2).build()
+
+  await website.crawl()
+
+  const port = await website.serveMetrics(0)
+
+  const body = await new Promise<string>((resolve, reject) => {
+    http
+      .get(`http://127.0.0.1:${port}/metrics`, (res) => {
+        let data = ''
+        res.on('data', (chunk) => (data += chunk))
+        res.on('end', () => resolve(data))
+      })
+      .on('error', reject)
+  })
+
+  website.stopMetrics()
+
+  t.assert(body.includes('spider_fetches_total'), 'should contain the fetch counter')
+  t.assert(body.includes('spider_fetch_duration_seconds_bucket'), 'should contain the latency histogram')
+  t.is(website.metricsText().split('\n')[0], body.split('\n')[0])
+})
+
+test('new website logger', async (t) => {
+  const records: NLogRecord[] = []
+
+  setLogger((_err: Error | null, record: NLogRecord) => {
+    records.push(record)
+  }, 'info')
+
+  const website = new Website(TEST_URL).withLimit(2).build()
+
+  await website.crawl()
+
+  setLogger()
+
+  t.assert(
+    records.some((record) => record.url && record.url.startsWith(TEST_URL)),
+    'should forward the fetch logs with the url',
+  )
+})
+
+test('new website errors report', async (t) => {
+  const website = new Website(`${TEST_URL}/not-a-real-page-404`)
+
+  await website.crawl()
+
+  const errors = website.getErrors()
+
+  t.assert(
+    errors.some((error) => error.stage === 'http' && error.statusCode === 404),
+    'should report the not found page',
+  )
+
+  website.clear()
+
+  t.is(website.getErrors().length, 0)
+})
+
+test('new website errors report attempts', async (t) => {
+  await withServer(
+    (req, res) => {
+      if (req.url === '/flaky') {
+        res.writeHead(503).end()
+      } else {
+        res.writeHead(200, { 'Content-Type': 'text/html' }).end('<html><body><a href="/flaky">flaky</a></body></html>')
+      }
+    },
+    async (url) => {
+      const website = new Website(url).withRetry(2)
+
+      await website.crawl()
+
+      const error = website.getErrors().find((error) => error.url === `${url}/flaky`)
+
+      t.is(error?.statusCode, 503)
+      t.is(error?.attempts, 3)
+      t.is(website.getConfig().retry, 2)
+    },
+  )
+})
+
+test('new website errors report robots', async (t) => {
+  await withServer(
+    (req, res) => {
+      if (req.url === '/robots.txt') {
+        res.writeHead(200, { 'Content-Type': 'text/plain' }).end('User-agent: *\nDisallow: /private')
+      } else {
+        res
+          .writeHead(200, { 'Content-Type': 'text/html' })
+          .end('<html><body><a href="/private">private</a></body></html>')
+      }
+    },
+    async (url) => {
+      const website = new Website(url).withRespectRobotsTxt(true)
+
+      await website.crawl()
+
+      const error = website.getErrors().find((error) => error.stage === 'robots')
+
+      t.is(error?.url, `${url}/private`)
+      t.is(error?.attempts, 0)
+    },
+  )
+})
+
+test('new website cookies import and export', async (t) => {
+  const website = new Website(TEST_URL).withCookies('theme=dark')
+
+  const imported = website.importCookies(
+    '# Netscape HTTP Cookie File\n.choosealicense.com\tTRUE\t/\tTRUE\t0\tsession\tabc123\n',
+    'netscape',
+  )
+
+  t.is(imported, 1)
+
+  const cookies = website.getCookies()
+
+  t.assert(
+    cookies.some((cookie) => cookie.name === 'theme' && cookie.value === 'dark'),
+    'should contain the cookie string',
+  )
+  t.assert(
+    cookies.some((cookie) => cookie.name === 'session' && cookie.domain === '.choosealicense.com'),
+    'should contain the imported cookie',
+  )
+
+  const json = JSON.parse(website.exportCookies('json'))
+
+  t.is(json.length, 2)
+  t.assert(website.exportCookies().includes('session\tabc123'), 'should export netscape cookies')
+})
+
+test('new website login', async (t) => {
+  const website = new Website(TEST_URL).withLogin({
+    url: TEST_URL,
+    method: 'GET',
+    successSelector: '.does-not-exist',
+  })
+
+  await t.throwsAsync(website.crawl(), { message: /login failed/ })
+
+  website.withLogin({ url: TEST_URL, method: 'GET', successSelector: 'body' }).withLimit(1)
+
+  await website.crawl()
+
+  t.is(website.getLinks().length, 1)
+})
+
+test('new website auth by origin', async (t) => {
+  const expected = `Basic ${Buffer.from('user:pass').toString('base64')}`
+  const statusCodes: number[] = []
+
+  await withServer(
+    (req, res) => {
+      if (req.headers.authorization === expected) {
+        res.writeHead(200, { 'Content-Type': 'text/html' }).end('<html><body>ok</body></html>')
+      } else {
+        res.writeHead(401, { 'WWW-Authenticate': 'Basic realm="test"' }).end()
+      }
+    },
+    async (url) => {
+      const website = new Website(url).withAuth({ [url]: { basic: { username: 'user', password: 'pass' } } })
+
+      await website.crawl((_, page) => {
+        statusCodes.push(page.statusCode)
+      })
+
+      website.withAuth({ 'http://localhost:1': { basic: { username: 'user', password: 'pass' } } })
+
+      await website.crawl((_, page) => {
+        statusCodes.push(page.statusCode)
+      })
+    },
+  )
+
+  t.deepEqual(statusCodes, [200, 401])
+})
+
+test('new website auth provider', async (t) => {
+  const statusCodes: number[] = []
+  let calls = 0
+
+  await withServer(
+    (req, res) => {
+      if (req.headers.authorization === 'Bearer token-2') {
+        res.writeHead(200, { 'Content-Type': 'text/html' }).end('<html><body>ok</body></html>')
+      } else {
+        res.writeHead(401).end()
+      }
+    },
+    async (url) => {
+      const website = new Website(url).withAuthProvider(async () => {
+        calls++
+        return { authorization: `Bearer token-${calls}` }
+      })
+
+      await website.crawl((_, page) => {
+        statusCodes.push(page.statusCode)
+      })
+
+      t.is(website.getConfig().headers?.authorization, undefined)
+    },
+  )
+
+  t.is(calls, 2)
+  t.deepEqual(statusCodes, [200])
+})
+
+test('new website sitemap', async (t) => {
+  const zlib = await import('node:zlib')
+  let base = ''
+
+  await withServer(
+    (req, res) => {
+      if (req.url === '/robots.txt') {
+        res.writeHead(200).end(`User-agent: *\nSitemap: ${base}/sitemap-index.xml`)
+      } else if (req.url === '/sitemap-index.xml') {
+        res
+          .writeHead(200, { 'Content-Type': 'application/xml' })
+          .end(
+            `<?xml version="1.0" encoding="UTF-8"?><sitemapindex><sitemap><loc>${base}/pages.xml</loc></sitemap><sitemap><loc>${base}/more.xml.gz</loc></sitemap></sitemapindex>`,
+          )
+      } else if (req.url === '/pages.xml') {
+        const xml = `<?xml version="1.0" encoding="UTF-8"?><urlset>
+          <url><loc>${base}/a</loc><lastmod>2024-01-01T00:00:00+00:00</lastmod><changefreq>daily</changefreq><priority>0.8</priority></url>
+          <url><loc>${base}/b</loc></url>
+        </urlset>`
+        res.writeHead(200, { 'Content-Type': 'application/xml' }).end(xml)
+      } else if (req.url === '/more.xml.gz') {
+        const xml = `<?xml version="1.0" encoding="UTF-8"?><urlset><url><loc>${base}/d</loc></url></urlset>`
+        res.writeHead(200).end(zlib.gzipSync(xml))
+      } else {
+        res.writeHead(200, { 'Content-Type': 'text/html' }).end('<html><body><a href="/c">c</a></body></html>')
+      }
+    },
+    async (url) => {
+      base = url
+
+      const website = new Website(base)
+      const entries = await website.getSitemapEntries()
+
+      t.deepEqual(
+        entries.map((entry) => entry.loc),
+        [`${base}/a`, `${base}/b`, `${base}/d`],
+      )
+      t.is(entries[0].lastmod, '2024-01-01T00:00:00+00:00')
+      t.is(entries[0].changefreq, 'daily')
+      t.is(entries[0].priority, 0.8)
+
+      const urls: string[] = []
+
+      await website.crawlSitemap((_, page) => urls.push(page.url))
+
+      t.deepEqual(urls.sort(), [`${base}/a`, `${base}/b`, `${base}/d`])
+      t.true(website.getLinks().includes(`${base}/a`))
+      t.true(website.getLinks().includes(`${base}/d`))
+      t.false(website.getLinks().includes(`${base}/c`))
+
+      const limited: string[] = []
+
+      await website.withLimit(2).crawlSitemap((_, page) => limited.push(page.url))
+
+      t.is(limited.length, 2)
+    },
+  )
+})
+
+test('robots rules offline', (t) => {
+  const robots = Robots.parse(
+    [
+      'User-agent: *',
+      'Disallow: /private',
+      'Disallow: /*?sort=',
+      'Crawl-delay: 2',
+      '',
+      'User-agent: spider',
+      'User-agent: other',
+      'Crawl-delay: 5',
+      '',
+      'Sitemap: https://example.com/sitemap.xml',
+    ].join('\n'),
+  )
+
+  t.is(robots.groups.length, 2)
+  t.deepEqual(robots.groups[1].userAgents, ['spider', 'other'])
+  t.deepEqual(robots.sitemaps, ['https://example.com/sitemap.xml'])
+  t.is(robots.crawlDelay(), 2)
+  t.is(robots.crawlDelay('spider-rs/1.0'), 5)
+
+  t.false(robots.isAllowed('https://example.com/private/page'))
+  t.true(robots.isAllowed('https://example.com/public'))
+  t.false(robots.isAllowed('/private/page', 'googlebot'))
+  t.true(robots.isAllowed('/robots.txt', 'spider-rs/1.0'))
+  t.false(robots.isAllowed('https://example.com/list?sort=asc'))
+  t.false(robots.isAllowed('/list?sort=asc'))
+  t.true(robots.isAllowed('/list?page=2'))
+  t.true(robots.isAllowed('/list'))
+})
+
+test('new website get robots', async (t) => {
+  await withServer(
+    (req, res) => {
+      const robots = 'User-agent: *\nCrawl-delay: 1\n\nUser-agent: spider\nCrawl-delay: 3'
+      res.writeHead(req.url === '/robots.txt' ? 200 : 404).end(robots)
+    },
+    async (url) => {
+      const robots = await new Website(url).withUserAgent('spider-rs/1.0').getRobots()
+
+      t.is(robots.url, `${url}/robots.txt`)
+      t.is(robots.crawlDelay(), 3)
+      t.is(robots.crawlDelay('googlebot'), 1)
+      t.true(robots.isAllowed('/'))
+    },
+  )
+})
+
+test('new website skipped urls', async (t) => {
+  await withServer(
+    (_, res) => {
+      res
+        .writeHead(200, { 'Content-Type': 'text/html' })
+        .end(
+          '<html><body><a href="/ok">ok</a><a href="/blocked">blocked</a><a href="/a/b">deep</a><a href="https://example.com/">external</a></body></html>',
+        )
+    },
+    async (url) => {
+      const website = new Website(url).withBlacklistUrl(['/blocked']).withDepth(1).withSkipReport(true)
+
+      await website.crawl()
+
+      const skipped = Object.fromEntries(website.getSkipped().map((skip) => [skip.url, skip.reason]))
+
+      t.is(skipped[`${url}/blocked`], 'blacklist')
+      t.is(skipped[`${url}/a/b`], 'depth')
+      t.is(skipped['https://example.com/'], 'external')
+      t.false(`${url}/ok` in skipped)
+    },
+  )
+})
+
+test('new website skipped urls by budget', async (t) => {
+  const pages: NPage[] = []
+
+  await withServer(
+    (req, res) => {
+      const body = req.url === '/' ? '<a href="/a">a</a><a href="/b">b</a>' : 'leaf'
+
+      res.writeHead(200, { 'Content-Type': 'text/html' }).end(`<html><body>${body}</body></html>`)
+    },
+    async (url) => {
+      const website = new Website(url).withBudget({ '*': 2 }).withSkipReport(true)
+
+      await website.crawl((_, page) => pages.push(page))
+
+      t.deepEqual(website.getSkipped().map((skip) => skip.reason), ['budget'])
+
+      website.withSkipReport(false)
+
+      t.false(website.getConfig().returnPageLinks)
+    },
+  )
+
+  t.true(pages.every((page) => page.links === undefined || page.links === null))
+})
+
+test('new website url normalization', async (t) => {
+  const pages: NPage[] = []
+  const url = await withServer(
+    (req, res) => {
+      const canonical = req.url === '/copy' ? '<link rel="canonical" href="/page?id=1">' : ''
+
+      res
+        .writeHead(200, { 'Content-Type': 'text/html' })
+        .end(
+          `<html><head>${canonical}</head><body><a href="/page?utm_source=news&id=1">a</a><a href="/page?id=1#top">b</a><a href="/other/">c</a><a href="/copy">d</a></body></html>`,
+        )
+    },
+    async (url) => {
+      const website = new Website(url).withUrlNormalization({
+        stripParams: ['utm_*'],
+        sortQuery: true,
+        lowercaseHost: true,
+        trailingSlash: false,
+        respectCanonical: true,
+      })
+
+      t.true(website.getConfig().urlNormalization?.lowercaseHost)
+      t.true(Website.fromConfig(website.getConfig()).getConfig().urlNormalization?.lowercaseHost)
+
+      await website.crawl((_, page) => pages.push(page))
+
+      return url
+    },
+  )
+
+  const normalized = pages.map((page) => page.normalizedUrl)
+
+  t.assert(normalized.includes(`${url}/page?id=1`), 'should strip the tracking params')
+  t.assert(normalized.includes(`${url}/other`), 'should remove the trailing slash')
+  t.is(new Set(normalized).size, normalized.length)
+  t.assert(pages.every((page) => page.originalUrl), 'should set the original url')
+})
+
+test('new website dedupe', async (t) => {
+  const boilerplate = 'Welcome to our store. Browse the catalog of products, read the reviews and contact support for help.'
+  const pages: NPage[] = []
+  const website = await withServer(
+    (req, res) => {
+      const body =
+        req.url === '/'
+          ? '<a href="/a">a</a><a href="/b">b</a><a href="/c">c</a>'
+          : req.url === '/c'
+            ? 'The license lets people do almost anything they want with your project like making closed source versions.'
+            : boilerplate
+
+      res.writeHead(200, { 'Content-Type': 'text/html' }).end(`<html><body><p>${body}</p></body></html>`)
+    },
+    async (url) => {
+      const website = new Website(url).withDedupe({ threshold: 3 })
+
+      await website.crawl((_, page) => pages.push(page))
+
+      return website
+    },
+  )
+
+  const duplicates = website.getDuplicates()
+
+  t.is(duplicates.length, 1)
+  t.is(website.stats().pagesDuplicate, 1)
+  t.is(pages.length, 3)
+  t.assert(pages.every((page) => page.fingerprint?.length === 16), 'should set the fingerprint')
+})
+
+test('new website change tracking', async (t) => {
+  const { promises } = await import('node:fs')
+  const { tmpdir } = await import('node:os')
+  const { join } = await import('node:path')
+  let run = 1
+
+  await withServer(
+    (req, res) => {
+      const body =
+        req.url === '/'
+          ? run === 1
+            ? '<a href="/a">a</a><a href="/b">b</a><a href="/c">c</a>'
+            : '<a href="/a">a</a><a href="/b">b</a>'
+          : req.url === '/b'
+            ? `b run ${run}`
+            : `${req.url} content`
+
+      res.writeHead(200, { 'Content-Type': 'text/html' }).end(`<html><body>${body}</body></html>`)
+    },
+    async (url) => {
+      const path = join(tmpdir(), `spider-changes-${new URL(url).port}.json`)
+      const crawlRun = async () => {
+        const statuses: Record<string, string | undefined> = {}
+        const website = new Website(url).withChangeTracking({ path })
+
+        await website.crawl((_, page) => {
+          statuses[page.url.replace(url, '')] = page.changeStatus
+        })
+
+        return { statuses, removed: website.getRemoved() }
+      }
+
+      try {
+        const first = await crawlRun()
+
+        run = 2
+
+        const second = await crawlRun()
+
+        t.is(first.statuses['/a'], 'new')
+        t.is(second.statuses['/a'], 'unchanged')
+        t.is(second.statuses['/b'], 'changed')
+        t.deepEqual(second.removed, [`${url}/c`])
+
+        run = 3
+
+        // the pages are hashed without a callback.
+        await new Website(url).withChangeTracking({ path }).crawl()
+
+        const third = await crawlRun()
+
+        t.is(third.statuses['/b'], 'unchanged')
+
+        // an empty run keeps the store.
+        await new Website('http://127.0.0.1:9').withChangeTracking({ path }).crawl()
+
+        const fourth = await crawlRun()
+
+        t.is(fourth.statuses['/a'], 'unchanged')
+      } finally {
+        await promises.rm(path, { force: true })
+      }
+    },
+  )
+})
+
+test('new website change tracking store', async (t) => {
+  const { promises } = await import('node:fs')
+  const { tmpdir } = await import('node:os')
+  const { join } = await import('node:path')
+  const links = Array.from({ length: 30 }, (_, i) => `<a href="/p${i}">${i}</a>`).join('')
+
+  await withServer(
+    (req, res) => {
+      if (req.url === '/gone') {
+        res.writeHead(404, { 'Content-Type': 'text/html' }).end('<html><body>missing</body></html>')
+      } else {
+        const body = req.url === '/' ? `${links}<a href="/gone">gone</a>` : `${req.url} content`
+
+        res.writeHead(200, { 'Content-Type': 'text/html' }).end(`<html><body>${body}</body></html>`)
+      }
+    },
+    async (url) => {
+      const path = join(tmpdir(), `spider-changes-store-${new URL(url).port}.json`)
+
+      try {
+        const statuses: Record<string, string | undefined> = {}
+
+        await new Website(url).withChangeTracking({ path }).crawl((_, page) => {
+          statuses[page.url.replace(url, '')] = page.changeStatus
+        })
+
+        t.is(statuses['/p0'], 'new')
+        t.true('/gone' in statuses)
+        t.is(statuses['/gone'], undefined, 'a missing page never crawled is not removed')
+
+        await promises.rm(path, { force: true })
+
+        // the pages hashed by the stats listener are saved when the crawl resolves.
+        await new Website(url).withChangeTracking({ path }).crawl()
+
+        const hashes = JSON.parse(await promises.readFile(path, 'utf8'))
+
+        t.is(Object.keys(hashes).length, 31)
+        t.truthy(hashes[`${url}/p29`])
+        t.false(`${url}/gone` in hashes)
+      } finally {
+        await promises.rm(path, { force: true })
+      }
+    },
+  )
+})
+
+test('new website proxy rotation', async (t) => {
+  const statusCodes: number[] = []
+
+  // the proxy stand-ins answer the proxied requests directly.
+  await withServer(
+    (_, res) => {
+      res
+        .writeHead(200, { 'Content-Type': 'text/html' })
+        .end('<html><body><a href="/a">a</a><a href="/b">b</a><a href="/c">c</a></body></html>')
+    },
+    (goodUrl) =>
+      withServer(
+        (_, res) => {
+          res.writeHead(502).end()
+        },
+        async (badUrl) => {
+          const website = new Website('http://spider.test')
+            .withProxies([badUrl, goodUrl])
+            .withProxyRotation({ strategy: 'round-robin', maxFailures: 1 })
+
+          await website.crawl((_, page) => {
+            statusCodes.push(page.statusCode)
+          })
+
+          const stats = website.getProxyStats()
+          const badStats = stats.find((s) => s.proxy === badUrl)
+          const goodStats = stats.find((s) => s.proxy === goodUrl)
+
+          t.assert(
+            statusCodes.length > 1 && statusCodes.every((code) => code === 200),
+            'should retry using the next proxy',
+          )
+          t.is(badStats?.ejections, 1)
+          t.is(badStats?.requests, 1)
+          t.is(goodStats?.requests, statusCodes.length)
+        },
+      ),
+  )
+})
+
+test('new website proxy rotation with socks', async (t) => {
+  const { createServer: createTcpServer, connect } = await import('node:net')
+  const statusCodes: number[] = []
+  let connections = 0
+
+  // the http proxy stand-in answers the proxied requests directly.
+  await withServer(
+    (_, res) => {
+      res
+        .writeHead(200, { 'Content-Type': 'text/html' })
+        .end('<html><body><a href="/a">a</a><a href="/b">b</a><a href="/c">c</a></body></html>')
+    },
+    async (httpUrl) => {
+      const target = new URL(httpUrl)
+      // the socks5 stand-in connects every request to the http server.
+      const socks = createTcpServer((socket) => {
+        socket.on('error', () => socket.destroy())
+        socket.once('data', () => {
+          socket.write(Buffer.from([5, 0]))
+          socket.once('data', () => {
+            const upstream = connect(Number(target.port), target.hostname, () => {
+              connections++
+              socket.write(Buffer.from([5, 0, 0, 1, 0, 0, 0, 0, 0, 0]))
+              upstream.pipe(socket)
+              socket.pipe(upstream)
+            })
+
+            upstream.on('error', () => socket.destroy())
+          })
+        })
+      })
+
+      await new Promise<void>((resolve) => socks.listen(0, '127.0.0.1', resolve))
+
+      try {
+        const socksUrl = `socks5h://127.0.0.1:${(socks.address() as AddressInfo).port}`
+        const website = new Website('http://spider.test')
+          .withProxies([socksUrl, httpUrl])
+          .withProxyRotation({ strategy: 'round-robin' })
+
+        await website.crawl((_, page) => {
+          statusCodes.push(page.statusCode)
+        })
+
+        const stats = website.getProxyStats()
+
+        t.assert(
+          statusCodes.length > 1 && statusCodes.every((code) => code === 200),
+          'should crawl through both proxies',
+        )
+        t.assert(connections > 0, 'should connect through the socks proxy')
+        t.is(stats.find((s) => s.proxy === socksUrl)?.failures, 0)
+        t.is(stats.find((s) => s.proxy === httpUrl)?.failures, 0)
+      } finally {
+        socks.close()
+      }
+    },
+  )
+})
+
+test('new website proxies with auth', async (t) => {
+  const statusCodes: number[] = []
+  const authorization = `Basic ${Buffer.from('user:pass').toString('base64')}`
+  const proxy: RequestListener = (req, res) => {
+    if (req.headers.authorization === authorization) {
+      res
+        .writeHead(200, { 'Content-Type': 'text/html' })
+        .end('<html><body><a href="/a">a</a><a href="/b">b</a><a href="/c">c</a></body></html>')
+    } else {
+      res.writeHead(401, { 'WWW-Authenticate': 'Basic realm="test"' }).end()
+    }
+  }
+
+  // the proxy stand-ins answer the proxied requests directly.
+  await withServer(proxy, (firstUrl) =>
+    withServer(proxy, async (secondUrl) => {
+      const website = new Website('http://spider.test')
+        .withProxies([firstUrl, secondUrl])
+        .withAuth({ 'http://spider.test': { basic: { username: 'user', password: 'pass' } } })
+
+      await website.crawl((_, page) => {
+        statusCodes.push(page.statusCode)
+      })
+
+      const stats = website.getProxyStats()
+
+      t.assert(
+        statusCodes.length > 1 && statusCodes.every((code) => code === 200),
+        'should send the credentials through the proxies',
+      )
+      t.is(stats.length, 2)
+      t.assert(stats.every((s) => s.requests > 0), 'should rotate the proxies')
+    }),
+  )
+})
+
 test('new single page', async (t) => {
   const page = new Page(TEST_URL)
   await page.fetch()
@@ -170,6 +947,279 @@ test.skip('new website native headless request interception', async (t) => {
   t.assert(website.getLinks().length > 1, 'should be more than one link')
 })
 
+test('new website resource blocking', async (t) => {
+  const pages: NPage[] = []
+
+  await withServer(
+    (_, res) => {
+      res
+        .writeHead(200, { 'Content-Type': 'text/html' })
+        .end(
+          '<html><body><script src="https://ads.example/a.js"></script><img src="https://cdn.ads.example/b.png">' +
+            '<script src="https://notads.example/c.js"></script><img src="/d.png?ref=ads.example"></body></html>',
+        )
+    },
+    async (url) => {
+      const website = new Website(url)
+        .withChromeIntercept(true, true)
+        .withResourceBlocking({ domains: ['ads.example'] })
+
+      await website.crawl((_, page) => pages.push(page))
+
+      t.deepEqual(website.withResourceBlocking(null).getConfig().chromeIntercept, {
+        enabled: true,
+        blockImages: true,
+      })
+    },
+  )
+
+  t.is(pages[0].blockedResources, 2)
+})
+
+test.skip('new website native headless resource blocking', async (t) => {
+  const website = new Website(TEST_URL).withResourceBlocking({
+    resourceTypes: ['image', 'font', 'media'],
+    urlPatterns: ['analytics'],
+    domains: ['doubleclick.net'],
+  })
+  const pages: NPage[] = []
+
+  await website.crawl((_, page) => pages.push(page), false, true)
+
+  t.assert(pages.length > 0, 'should have pages')
+  t.assert(
+    pages.every((page) => typeof page.blockedResources === 'number'),
+    'should count the blocked resources',
+  )
+})
+
+test.skip('new website native headless automation scripts', async (t) => {
+  const website = new Website(TEST_URL)
+    .withAutomationScripts({ '/': [{ scroll: 1000 }, { evaluate: 'document.body.dataset.scrolled = "true"' }] })
+    .withLimit(1)
+
+  const htmls: string[] = []
+
+  await website.crawl((_, page) => htmls.push(page.content), false, true)
+
+  t.assert(htmls[0].includes('data-scrolled="true"'), 'should run the automation scripts')
+})
+
+test.skip('new website native headless evaluate scripts', async (t) => {
+  const website = new Website(TEST_URL)
+    .withEvaluateScripts({ '/': 'Promise.resolve({ title: document.title })' })
+    .withLimit(1)
+
+  const pages: NPage[] = []
+
+  await website.crawl((_, page) => pages.push(page), false, true)
+
+  t.is(pages[0].evaluated?.title, pageTitle(pages[0]))
+  t.assert(!pages[0].content.includes('spider-evaluated'), 'should remove the evaluated result from the content')
+})
+
+test.skip('new website native headless screenshot and pdf', async (t) => {
+  const website = new Website(TEST_URL)
+    .withScreenshot({
+      params: { cdp_params: null, full_page: false, omit_background: false },
+      bytes: true,
+      save: false,
+      output_dir: null,
+    })
+    .withPdf({ printBackground: true })
+    .withLimit(1)
+
+  const pages: NPage[] = []
+
+  await website.crawl((_, page) => pages.push(page), false, true)
+
+  t.assert(pages[0].screenshot && pages[0].screenshot.length > 0, 'should contain the screenshot bytes')
+  t.is(pages[0].pdf?.subarray(0, 4).toString(), '%PDF')
+})
+
+test('new website pdf only for headless crawls', async (t) => {
+  const pages: NPage[] = []
+
+  await withServer(
+    (_, res) => {
+      res.writeHead(200, { 'Content-Type': 'text/html' }).end('<html><body>pdf</body></html>')
+    },
+    async (url) => {
+      const website = new Website(url).withPdf({ printBackground: true })
+
+      await website.crawl((_, page) => pages.push(page))
+
+      t.falsy(website.getConfig().chromeConnection)
+    },
+  )
+
+  t.is(pages.length, 1)
+  t.falsy(pages[0].pdf)
+})
+
+test('new website device preset', async (t) => {
+  const website = new Website(TEST_URL).withDevice('iphone-15').withTimezone('Europe/Paris').withLocale('fr-FR')
+  const config = website.getConfig()
+
+  t.deepEqual(config.viewport, {
+    width: 393,
+    height: 852,
+    deviceScaleFactor: 3,
+    mobile: true,
+    landscape: false,
+    hasTouch: true,
+  })
+  t.regex(config.userAgent ?? '', /iPhone OS 17_4/)
+  t.is(config.timezone, 'Europe/Paris')
+  t.is(config.locale, 'fr-FR')
+
+  t.throws(() => website.withDevice('unknown'), { message: /unknown device unknown/ })
+})
+
+test.skip('new website native headless viewport', async (t) => {
+  const website = new Website(TEST_URL)
+    .withViewport({ width: 390, height: 844, mobile: true })
+    .withEvaluateScripts({ '/': '({ width: window.innerWidth, timezone: Intl.DateTimeFormat().resolvedOptions().timeZone })' })
+    .withTimezone('Asia/Tokyo')
+    .withLimit(1)
+
+  const pages: NPage[] = []
+
+  await website.crawl((_, page) => pages.push(page), false, true)
+
+  t.deepEqual(pages[0].evaluated, { width: 390, timezone: 'Asia/Tokyo' })
+})
+
+test('new website from config', async (t) => {
+  const website = Website.fromConfig({ url: TEST_URL, limit: 2, budget: { '*': 10 }, redirectPolicy: 'strict' })
+
+  await website.crawl()
+
+  t.assert(website.getLinks().length <= 2, 'should be at most two links')
+  t.throws(() => Website.fromConfig({ url: TEST_URL, dpeth: 2 } as NWebsiteConfig), {
+    message: /unknown field `dpeth`/,
+  })
+})
+
+test('new website get config and clone', async (t) => {
+  const website = new Website(TEST_URL).withDepth(3).withBudget({ '*': 5 }).withRedirectPolicy(true)
+  const config = website.getConfig()
+
+  t.like(config, { depth: 3, budget: { '*': 5 }, redirectPolicy: 'strict', respectRobotsTxt: false })
+
+  const copy = website.clone()
+
+  t.deepEqual(copy.getConfig(), config)
+
+  copy.withDepth(1)
+
+  t.is(website.getConfig().depth, 3)
+  t.is(copy.getConfig().depth, 1)
+})
+
+test('new website get config redacts the secrets', async (t) => {
+  const website = new Website(TEST_URL)
+    .withHeaders({
+      authorization: 'Bearer secret',
+      cookie: 'session=secret',
+      'x-api-key': 'secret',
+      accept: 'text/html',
+    })
+    .withCookies('session=secret')
+    .withOpenai({ model: 'gpt-4', prompt: 'Search for movies', max_tokens: 256, api_key: 'secret' })
+    .withAuth({ [TEST_URL]: { basic: { username: 'user', password: 'secret' } } })
+    .withLogin({ url: `${TEST_URL}/login`, form: { user: 'user', pass: 'secret' } })
+  const config = website.getConfig()
+
+  t.is(config.headers?.authorization, '[redacted]')
+  t.is(config.headers?.cookie, '[redacted]')
+  t.is(config.headers?.['x-api-key'], '[redacted]')
+  t.is(config.headers?.accept, 'text/html')
+  t.deepEqual((config.cookies as NCookie[]).map((cookie) => [cookie.name, cookie.value]), [['session', '[redacted]']])
+  t.is(config.openai.api_key, '[redacted]')
+  t.is(config.openai.model, 'gpt-4')
+  t.deepEqual(config.auth?.[TEST_URL].basic, { username: 'user', password: '[redacted]' })
+  t.deepEqual(config.login?.form, { user: '[redacted]', pass: '[redacted]' })
+  t.deepEqual(website.clone().getConfig(), config)
+})
+
+test('new website from config file', async (t) => {
+  const { promises } = await import('node:fs')
+  const { tmpdir } = await import('node:os')
+  const path = `${tmpdir()}/spider-config.yaml`
+
+  await promises.writeFile(path, `url: ${TEST_URL}\nlimit: 1\nwaitForDelay:\n  seconds: 1\n`)
+
+  const website = Website.fromConfigFile(path)
+
+  await website.crawl()
+  await promises.unlink(path)
+
+  t.assert(website.getLinks().length <= 1, 'should be at most one link')
+})
+
+test('new crawl pool', async (t) => {
+  const pool = new CrawlPool({ concurrency: 4, perDomain: 2 })
+
+  pool.add(TEST_URL).addWebsite(new Website('https://rsseau.fr').withLimit(2))
+
+  t.is(pool.size, 2)
+
+  const pages: NPage[] = []
+
+  const results = await pool.crawl((_, page) => pages.push(page))
+
+  t.is(results.length, 2)
+  t.assert(results.every((site) => site.stats.pagesFetched > 0), 'every site should fetch pages')
+  t.assert(pages.length > 2, 'should stream the pages of every site')
+})
+
+test('new browser pool stats', async (t) => {
+  const pool = new BrowserPool({ browsers: 2, tabsPerBrowser: 3 })
+
+  new Website(TEST_URL).withBrowserPool(pool)
+
+  t.like(pool.getStats(), { browsers: 2, running: 0, busy: 0, waiting: 0, tabsPerBrowser: 3, pagesServed: 0 })
+
+  await pool.close()
+})
+
+test('new browser pool only for headless crawls', async (t) => {
+  const pool = new BrowserPool({ browsers: 1 })
+
+  await withServer(
+    (_, res) => {
+      res.writeHead(200, { 'Content-Type': 'text/html' }).end('<html><body>pool</body></html>')
+    },
+    async (url) => {
+      const website = new Website(url).withBrowserPool(pool)
+
+      await website.crawl()
+
+      t.falsy(website.getConfig().chromeConnection)
+    },
+  )
+
+  t.like(pool.getStats(), { running: 0, busy: 0, pagesServed: 0 })
+
+  await pool.close()
+})
+
+test.skip('new website native headless browser pool', async (t) => {
+  const pool = new BrowserPool({ browsers: 1, tabsPerBrowser: 2, recycleAfter: 1 })
+  const websites = [new Website(TEST_URL).withLimit(2), new Website(TEST_URL).withLimit(2)]
+
+  await Promise.all(websites.map((website) => website.withBrowserPool(pool).crawl(undefined, false, true)))
+
+  const stats = pool.getStats()
+
+  t.is(stats.busy, 0)
+  t.assert(stats.pagesServed > 0, 'should serve pages')
+  t.assert(stats.recycled > 0, 'should recycle the browser')
+
+  await pool.close()
+})
+
 test('new website native raw content', async (t) => {
   const website = new Website(TEST_URL, true)
 
@@ -3,6 +3,323 @@
 
 /* auto-generated by NAPI-RS */
 
+/** the username and password for an origin. */
+export interface NAuthCredentials {
+  /** The username. */
+  username: string
+  /** The password. */
+  password: string
+}
+/** the credentials used for an origin. */
+export interface NAuth {
+  /** Send the credentials using HTTP Basic auth with every request to the origin. */
+  basic?: NAuthCredentials
+  /** Answer HTTP Digest challenges from the origin. */
+  digest?: NAuthCredentials
+}
+/** an input to fill. */
+export interface NFillStep {
+  /** The CSS selector of the input. */
+  selector: string
+  /** The value to type. */
+  value: string
+}
+/** an action to run on the page after navigation. Set one action per step. */
+export interface NAutomationStep {
+  /** Click on the element matching the CSS selector. */
+  click?: string
+  /** Click on all of the elements matching the CSS selector. */
+  clickAll?: string
+  /** Scroll vertically by the amount of pixels. */
+  scroll?: number
+  /** Scroll horizontally by the amount of pixels. */
+  scrollX?: number
+  /** Scroll to the bottom of the page the amount of times to load more content. */
+  infiniteScroll?: number
+  /** Fill the input with the value. */
+  fill?: NFillStep
+  /** Wait for the amount of milliseconds. */
+  wait?: number
+  /** Wait for the element matching the CSS selector. */
+  waitFor?: string
+  /** Wait for the next navigation. */
+  waitForNavigation?: boolean
+  /** Run the javascript on the page. */
+  evaluate?: string
+}
+/** the resources to block in headless mode. */
+export interface NResourceBlocking {
+  /** The resource types to block. */
+  resourceTypes?: Array<'image' | 'stylesheet' | 'font' | 'media' | 'script'>
+  /** Block the requests with a url containing one of the patterns. */
+  urlPatterns?: Array<string>
+  /** Block the requests to the domains and their subdomains like third party trackers and ads. */
+  domains?: Array<string>
+}
+/** a duration to wait for. */
+export interface NWaitFor {
+  /** The seconds to wait. */
+  seconds?: number
+  /** The nanoseconds to wait. */
+  nanos?: number
+}
+/** an element to wait for. */
+export interface NWaitForSelector {
+  /** The CSS selector of the element. */
+  selector: string
+  /** The seconds to wait at most. */
+  seconds?: number
+  /** The nanoseconds to wait at most. */
+  nanos?: number
+}
+/** the chrome network interception. */
+export interface NChromeIntercept {
+  /** Only allow the content that matches the host. */
+  enabled: boolean
+  /** Block the images. Defaults to false. */
+  blockImages?: boolean
+}
+/** the cron job to run. */
+export interface NCron {
+  /** The cron expression. */
+  expression: string
+  /** The job to run. Defaults to crawl. */
+  cronType?: 'crawl' | 'scrape'
+}
+/** the website configuration with every builder option by the builder method name. */
+export interface NWebsiteConfig {
+  /** The url to crawl. */
+  url: string
+  /** Do not convert the content to UTF-8. */
+  rawContent?: boolean
+  /** The HTTP headers. */
+  headers?: Record<string, string>
+  /** The cookies as a Cookie header string or a list of cookies. */
+  cookies?: string | Array<NCookie>
+  /** The login step to run before the crawl. */
+  login?: NLoginConfig
+  /** The credentials by origin. */
+  auth?: Record<string, NAuth>
+  /** The user agent. */
+  userAgent?: string
+  /** Respect the robots.txt file. */
+  respectRobotsTxt?: boolean
+  /** Collect all the resources found on the pages. */
+  fullResources?: boolean
+  /** The chrome network interception. */
+  chromeIntercept?: NChromeIntercept
+  /** The resources to block in headless mode. */
+  resourceBlocking?: NResourceBlocking
+  /** The url normalization. */
+  urlNormalization?: NUrlNormalization
+  /** The near-duplicate page detection. */
+  dedupe?: NDedupe
+  /** The change detection between runs. */
+  changeTracking?: NChangeTracking
+  /** The connection url for the chrome instance. */
+  chromeConnection?: string
+  /** Preserve the HOST header. */
+  preserveHostHeader?: boolean
+  /** Crawl the subdomains. */
+  subdomains?: boolean
+  /** Crawl the TLD pages. */
+  tld?: boolean
+  /** Only use HTTP/2. */
+  http2PriorKnowledge?: boolean
+  /** The request timeout per page in milliseconds. */
+  requestTimeout?: number
+  /** The crawl timeout in milliseconds. */
+  crawlTimeout?: number
+  /** The amount of pages to crawl. */
+  limit?: number
+  /** The external domains to crawl. */
+  externalDomains?: Array<string>
+  /** The device preset. The viewport and user agent options take priority. */
+  device?: string
+  /** The browser viewport. */
+  viewport?: NViewport
+  /** The browser timezone. */
+  timezone?: string
+  /** The browser locale. */
+  locale?: string
+  /** Use stealth mode for headless requests. Not reported when the default stealth mode of chrome is kept. */
+  stealth?: boolean
+  /** Accept invalid certificates. */
+  dangerAcceptInvalidCerts?: boolean
+  /** The crawl budget by path. */
+  budget?: Record<string, number>
+  /** The max redirects allowed for a request. */
+  redirectLimit?: number
+  /** The redirect policy. */
+  redirectPolicy?: 'strict' | 'loose'
+  /** The regex blacklist urls. */
+  blacklistUrl?: Array<string>
+  /** The regex whitelist urls. */
+  whitelistUrl?: Array<string>
+  /** Wait for a delay on the page in headless mode. */
+  waitForDelay?: NWaitFor
+  /** Wait for an element on the page in headless mode. */
+  waitForSelector?: NWaitForSelector
+  /** Wait for the network to be idle in headless mode. */
+  waitForIdleNetwork?: NWaitFor
+  /** The automation steps by url path. */
+  automationScripts?: Record<string, Array<NAutomationStep>>
+  /** The javascript expressions to evaluate by url path. */
+  evaluateScripts?: Record<string, string>
+  /** The cron job. */
+  cron?: NCron
+  /** The OpenAI configs. */
+  openai?: any
+  /** The screenshot configs. */
+  screenshot?: any
+  /** The PDF render options. */
+  pdf?: NPdfOptions
+  /** The delay between requests in milliseconds. */
+  delay?: number
+  /** The crawl depth limit. */
+  depth?: number
+  /** The amount of retries for the failed requests. */
+  retry?: number
+  /** Return the links found on the pages. */
+  returnPageLinks?: boolean
+  /** Report the urls skipped by the crawl rules. */
+  skipReport?: boolean
+  /** Cache the pages following HTTP rules. */
+  caching?: boolean
+  /** The sitemap url. */
+  sitemap?: string
+  /** The proxies. */
+  proxies?: Array<string>
+  /** The proxy rotation. */
+  proxyRotation?: NProxyRotation
+}
+/** the browser viewport. */
+export interface NViewport {
+  /** The screen width in pixels. */
+  width: number
+  /** The screen height in pixels. */
+  height: number
+  /** The device scale factor. Defaults to 1. */
+  deviceScaleFactor?: number
+  /** Emulate a mobile device. Defaults to false. */
+  mobile?: boolean
+  /** Use the landscape orientation. Defaults to false. */
+  landscape?: boolean
+  /** Emulate a touch screen. Defaults to the mobile value. */
+  hasTouch?: boolean
+}
+/** a structured log record from the crawler. */
+export interface NLogRecord {
+  /** The level of the record: error, warn, info, debug or trace. */
+  level: string
+  /** The target of the record, usually the rust module that emitted it. */
+  target: string
+  /** The message of the record. */
+  message: string
+  /** The url found in the message. */
+  url?: string
+  /** The error detail of warn and error records. */
+  error?: string
+  /** The rust module path that emitted the record. */
+  modulePath?: string
+  /** The source file that emitted the record. */
+  file?: string
+  /** The source line that emitted the record. */
+  line?: number
+}
+/** Forward the crawler logs to the callback. The level is one of error, warn, info, debug or trace and defaults to info. Pass no callback to stop logging. */
+export declare function setLogger(callback?: (err: Error | null, arg: NLogRecord) => any | undefined | null, level?: string | undefined | null): void
+/** the login step to run before the crawl. */
+export interface NLoginConfig {
+  /** The url of the login page or form action. */
+  url: string
+  /** The HTTP method to submit the form with. Defaults to POST. */
+  method?: string
+  /** The form fields by input name. */
+  form?: Record<string, string>
+  /** A CSS selector that is only found on the page after a successful login. */
+  successSelector?: string
+  /** Fill and submit the form using headless chrome instead of HTTP. */
+  headless?: boolean
+}
+/** how the proxies are chosen for the requests. */
+export interface NProxyRotation {
+  /** The rotation strategy. Defaults to round-robin. */
+  strategy?: 'round-robin' | 'random' | 'sticky-per-host' | 'least-failures'
+  /** The consecutive failures before a proxy is ejected. Defaults to 3. */
+  maxFailures?: number
+  /** The time in milliseconds an ejected proxy is not used. Defaults to 60000. */
+  ejectDuration?: number
+}
+/** the health of a proxy during the crawl. */
+export interface NProxyStats {
+  /** The proxy url. */
+  proxy: string
+  /** The amount of requests sent using the proxy. */
+  requests: number
+  /** The amount of requests that failed using the proxy. */
+  failures: number
+  /** The amount of times the proxy was ejected. */
+  ejections: number
+  /** Is the proxy currently ejected. */
+  ejected: boolean
+  /** The average request duration in milliseconds. */
+  averageLatency: number
+}
+/** the rules of a robots.txt group. */
+export interface NRobotsGroup {
+  /** The user agents of the group. */
+  userAgents: Array<string>
+  /** The allowed path patterns. */
+  allow: Array<string>
+  /** The disallowed path patterns. */
+  disallow: Array<string>
+  /** The delay between requests in seconds. */
+  crawlDelay?: number
+}
+/** the PDF render options. */
+export interface NPdfOptions {
+  /** Use the landscape orientation. Defaults to false. */
+  landscape?: boolean
+  /** Print the background graphics. Defaults to false. */
+  printBackground?: boolean
+  /** The scale of the page rendering. Defaults to 1. */
+  scale?: number
+  /** The paper width in inches. Defaults to 8.5. */
+  paperWidth?: number
+  /** The paper height in inches. Defaults to 11. */
+  paperHeight?: number
+  /** Prefer the page size defined by css. Defaults to false. */
+  preferCssPageSize?: boolean
+}
+/** the browser pool options. */
+export interface NBrowserPoolOptions {
+  /** The amount of chrome instances. Defaults to 1. */
+  browsers?: number
+  /** The amount of tabs open at the same time per chrome instance. Defaults to 4. */
+  tabsPerBrowser?: number
+  /** Restart the chrome instance after serving the amount of pages. The restart happens once the crawl using it finishes. Defaults to 0 to never recycle. */
+  recycleAfter?: number
+}
+/** the browser pool statistics. */
+export interface NBrowserPoolStats {
+  /** The amount of chrome instances in the pool. */
+  browsers: number
+  /** The amount of chrome instances running. */
+  running: number
+  /** The amount of chrome instances used by a crawl. */
+  busy: number
+  /** The amount of crawls waiting for a chrome instance. */
+  waiting: number
+  /** The amount of tabs allowed per chrome instance. */
+  tabsPerBrowser: number
+  /** The amount of pages served by the pool. */
+  pagesServed: number
+  /** The amount of chrome instances restarted after serving the recycle amount of pages. */
+  recycled: number
+  /** The amount of chrome instances restarted after a crash. */
+  restarts: number
+}
 /** a simple page object */
 export interface NPage {
   /** The url found. */
@@ -17,14 +334,164 @@ export interface NPage {
   headers?: Record<string, string>
   /** The links found on the page. Requires the website.builder method website.with_subscription_return_page_links to be set to true. */
   links?: Array<string>
+  /** The JSON result of the expression evaluated on the page in headless mode. Requires the website.builder method website.with_evaluate_scripts to be set. */
+  evaluated?: any
+  /** The screenshot bytes. Requires the website.builder method website.with_screenshot to be set with bytes enabled. */
+  screenshot?: Buffer
+  /** The PDF render of the page. Requires the website.builder method website.with_pdf to be set. */
+  pdf?: Buffer
+  /** The amount of resources referenced by the page html matching the blocking rules. The resources requested by scripts are not counted. Requires the website.builder method website.with_resource_blocking to be set. */
+  blockedResources?: number
+  /** The url found before the normalization. Requires the website.builder method website.with_url_normalization to be set. */
+  originalUrl?: string
+  /** The normalized url using the canonical link when respected. Requires the website.builder method website.with_url_normalization to be set. */
+  normalizedUrl?: string
+  /** The SimHash fingerprint of the page text as hex. Requires the website.builder method website.with_dedupe to be set. */
+  fingerprint?: string
+  /** The change of the page since the last run. Removed pages are sent at the end of the run. Requires the website.builder method website.with_change_tracking to be set. */
+  changeStatus?: 'new' | 'changed' | 'unchanged' | 'removed'
+}
+/** the change detection options. */
+export interface NChangeTracking {
+  /** The JSON file storing the content hashes by url between runs. The hashes are kept in memory for the website when not set. */
+  path?: string
+}
+/** the near-duplicate page detection options. */
+export interface NDedupe {
+  /** The max amount of bits different between the fingerprints of near-duplicate pages from 0 to 64. Defaults to 3. */
+  threshold?: number
+}
+/** a near-duplicate page that was not sent. */
+export interface NDuplicatePage {
+  /** The url of the page. */
+  url: string
+  /** The url of the page sent with similar content. */
+  duplicateOf: string
+  /** The amount of bits different between the fingerprints. */
+  distance: number
+}
+/** the url normalization applied to the links before they are crawled. */
+export interface NUrlNormalization {
+  /** The query params to remove. A trailing * removes the params starting with the prefix like utm_*. */
+  stripParams?: Array<string>
+  /** Sort the query params by name. */
+  sortQuery?: boolean
+  /** Lowercase the host. The WHATWG url parsing already lowercases the hosts of the links. */
+  lowercaseHost?: boolean
+  /** Add the trailing slash to the paths when true or remove it when false. Keeps the path as found when not set. */
+  trailingSlash?: boolean
+  /** Use the canonical link of the page as the normalized url. Pages with a canonical url already sent are skipped. */
+  respectCanonical?: boolean
 }
 /** get the page title. */
 export declare function pageTitle(page: NPage): string
 /** crawl a website using HTTP gathering all links and html. */
 export declare function crawl(url: string, rawContent?: boolean | undefined | null): Promise<NWebsite>
+/** a cookie used for the crawl. */
+export interface NCookie {
+  /** The name of the cookie. */
+  name: string
+  /** The value of the cookie. */
+  value: string
+  /** The domain of the cookie. A leading dot includes the subdomains. */
+  domain: string
+  /** The path of the cookie. Defaults to /. */
+  path?: string
+  /** The expiration as unix time in seconds. Session cookies do not expire. */
+  expires?: number
+  /** Only send the cookie over HTTPS. */
+  secure?: boolean
+  /** The cookie is not accessible from javascript. */
+  httpOnly?: boolean
+}
+/** a url listed in a sitemap. */
+export interface NSitemapEntry {
+  /** The url of the page. */
+  loc: string
+  /** The date the page was last modified as RFC 3339. */
+  lastmod?: string
+  /** How frequently the page is likely to change. */
+  changefreq?: string
+  /** The priority of the page relative to the other pages of the site. */
+  priority?: number
+}
+/** a url found during the crawl that was not crawled. */
+export interface NSkippedUrl {
+  /** The url skipped. */
+  url: string
+  /** The rule that excluded the url. */
+  reason: 'external' | 'blacklist' | 'whitelist' | 'robots' | 'depth' | 'budget'
+  /** The page the url was found on. */
+  foundOn?: string
+}
+/** the crawl pool options. */
+export interface NCrawlPoolOptions {
+  /** The amount of requests running at the same time across all sites. Defaults to 100. The headless crawls are not counted. */
+  concurrency?: number
+  /** The amount of requests running at the same time per domain. Defaults to 10. */
+  perDomain?: number
+  /** The amount of sites crawling at the same time. Defaults to 0 to crawl all sites at once. */
+  maxSites?: number
+}
+/** the result of a site crawled by the pool. */
+export interface NSiteResult {
+  /** The url of the site. */
+  url: string
+  /** The crawl status. */
+  status: string
+  /** The crawl statistics of the site. */
+  stats: NCrawlStats
+  /** The error when the crawl failed to start or stopped unexpectedly. */
+  error?: string
+}
+/** a page that failed during the crawl. */
+export interface NCrawlError {
+  /** The url that failed. */
+  url: string
+  /** The stage the request failed at: dns, connect, tls, timeout, http, parse or robots. */
+  stage: 'dns' | 'connect' | 'tls' | 'timeout' | 'http' | 'parse' | 'robots'
+  /** The error message. */
+  message: string
+  /** The HTTP status code. */
+  statusCode: number
+  /** The amount of attempts made for the url. */
+  attempts: number
+}
+/** the live crawl statistics. */
+export interface NCrawlStats {
+  /** The amount of pages fetched including failed pages. */
+  pagesFetched: number
+  /** The amount of pages found that are not fetched yet. */
+  pagesQueued: number
+  /** The amount of pages that returned an error status code. */
+  pagesFailed: number
+  /** The amount of near-duplicate pages not sent. Requires the website.builder method website.with_dedupe to be set. */
+  pagesDuplicate: number
+  /** The amount of bytes downloaded. */
+  bytesDownloaded: number
+  /** The amount of pages fetched per second. */
+  requestsPerSecond: number
+  /** The amount of pages per HTTP status code. */
+  statusCodes: Record<string, number>
+  /** The time elapsed in milliseconds since the crawl started. */
+  elapsed: number
+}
 export interface PageEvent {
   page: NPage
 }
+/** crawl many sites sharing the request limits and the page subscription. The limits are held by the HTTP client so the headless crawls, fetching the pages in chrome, are only limited by the crawl concurrency of each site capped to the domain limit and the global limit does not apply to them. */
+export class CrawlPool {
+  /** create a new crawl pool. */
+  constructor(options?: NCrawlPoolOptions | undefined | null)
+  /** add a site to crawl by url. */
+  add(url: string): this
+  /** add a copy of the website to crawl with its configuration and callbacks. The website passed in is not crawled, use the pool results for the pages and status. */
+  addWebsite(website: Website): this
+  /** the amount of sites in the pool. */
+  get size(): number
+  /** crawl all of the sites sending the pages of every site to one callback. The site callback runs when a site finishes. */
+  crawl(onPageEvent?: (err: Error | null, arg: NPage) => any | undefined | null, onSiteComplete?: (err: Error | null, arg: NSiteResult) => any | undefined | null, headless?: boolean | undefined | null): Promise<Array<NSiteResult>>
+}
 /** website main data from rust to node. */
 export class NWebsite {
   /** all of the website links. */
@@ -53,11 +520,43 @@ export class Page {
   /** get the bytes for the page */
   getBytes(): Uint8Array
 }
+/** a pool of local chrome instances shared by the websites for headless crawls. */
+export class BrowserPool {
+  /** create a new browser pool. The chrome instances launch on first use. */
+  constructor(options?: NBrowserPoolOptions | undefined | null)
+  /** get the pool statistics. */
+  getStats(): NBrowserPoolStats
+  /** close the chrome instances that are not used by a crawl. */
+  close(): Promise<void>
+}
+/** the parsed robots.txt of a host. */
+export class Robots {
+  /** parse the content of a robots.txt file. */
+  static parse(content: string, url?: string | undefined | null): Robots
+  /** the url of the robots.txt. */
+  get url(): string
+  /** the groups of rules by user agent. */
+  get groups(): Array<NRobotsGroup>
+  /** the sitemaps listed. */
+  get sitemaps(): Array<string>
+  /** is the url or path allowed for the user agent with the rules the crawl respects. The rules are matched on the path and the query. The user agent of the website is used by default. */
+  isAllowed(url: string, userAgent?: string | undefined | null): boolean
+  /** the delay between requests in seconds for the user agent. The user agent of the website is used by default. */
+  crawlDelay(userAgent?: string | undefined | null): number | null
+}
 /** a website holding the inner spider::website::Website from Rust fit for nodejs. */
 export class Website {
   /** a new website. */
   constructor(url: string, rawContent?: boolean | undefined | null)
-  /** Get the crawl status. */
+  /** a new website from a config object with the builder options by method name. Unknown keys are errors. */
+  static fromConfig(config: NWebsiteConfig): Website
+  /** a new website from a JSON or YAML config file with the builder options by method name. Unknown keys are errors. */
+  static fromConfigFile(path: string): Website
+  /** get the effective configuration including the defaults. The result can be passed to `Website.fromConfig`. The device preset shows as the viewport and user agent. The passwords, login form values, cookie values, OpenAI api keys, credential headers and proxy passwords are redacted. */
+  getConfig(): NWebsiteConfig
+  /** create an independent copy of the website with the same configuration and callbacks. The crawl state, links and data are not copied. */
+  clone(): Website
+  /** Get the crawl status. This is `TimedOut` or `LimitReached` when the crawl ended from `website.withCrawlTimeout` or `website.withLimit`. */
   get status(): string
   /** Store data to heap memory. The data must be an object. Use `website.export_jsonl_data` to store to disk. When using this method test occordingly since only certain primitives are supported. */
   pushData(obj: unknown): void
@@ -75,6 +574,8 @@ export class Website {
   stop(id?: number | undefined | null): Promise<boolean>
   /** crawl a website */
   crawl(onPageEvent?: (err: Error | null, arg: NPage) => any | undefined | null, background?: boolean | undefined | null, headless?: boolean | undefined | null): Promise<void>
+  /** Crawl only the urls listed in the sitemaps including the sitemap indexes, the gzip sitemaps and the sitemaps listed in robots.txt. The links found on the pages are not followed. */
+  crawlSitemap(onPageEvent?: (err: Error | null, arg: NPage) => any | undefined | null, headless?: boolean | undefined | null): Promise<void>
   /** Start to crawl website with async concurrency smart. Use HTTP first and JavaScript Rendering as needed. */
   crawlSmart(onPageEvent?: (err: Error | null, arg: NPage) => any | undefined | null, background?: boolean | undefined | null): Promise<void>
   /** scrape a website */
@@ -85,22 +586,64 @@ export class Website {
   getLinks(): Array<string>
   /** get the size of the website in amount of pages crawled. If you ran the page in the background, this value will not update. */
   get size(): number
+  /** get the live crawl statistics. This value updates for crawls running in the background. */
+  stats(): NCrawlStats
   /** get all the pages of a website - requires calling website.scrape */
   getPages(): Array<NPage>
+  /** get the pages that failed during the last crawl. The errors are cleared with `website.clear`. */
+  getErrors(): Array<NCrawlError>
+  /** get the near-duplicate pages found during the last crawl. Requires `website.withDedupe`. */
+  getDuplicates(): Array<NDuplicatePage>
+  /** get the urls of the previous run that were not found in the last run. Requires `website.withChangeTracking`. */
+  getRemoved(): Array<string>
+  /** get the urls found during the last crawl that were not crawled with the rule that excluded them: external, blacklist, whitelist, robots, depth or budget. Requires `website.withSkipReport`. */
+  getSkipped(): Array<NSkippedUrl>
+  /** get the cookies imported and received during the crawl. */
+  getCookies(): Array<NCookie>
+  /** import cookies using the `netscape` cookies.txt or `json` format. Defaults to `netscape`. Returns the amount of cookies imported. */
+  importCookies(data: string, format?: string | undefined | null): number
+  /** export the cookies using the `netscape` cookies.txt or `json` format. Defaults to `netscape`. */
+  exportCookies(format?: string | undefined | null): string
+  /** get the crawl metrics using the Prometheus text format. The metrics are collected across all runs of the website. */
+  metricsText(): string
+  /** serve the crawl metrics using the Prometheus text format over HTTP. Use port 0 to bind to any open port. Returns the port bound. */
+  serveMetrics(port?: number | undefined | null, host?: string | undefined | null): Promise<number>
+  /** stop serving the crawl metrics. */
+  stopMetrics(): boolean
   /** drain all links from storing */
   drainLinks(): Array<string>
   /** clear all links and page data */
   clear(): void
   /** Set HTTP headers for request using [reqwest::header::HeaderMap](https://docs.rs/reqwest/latest/reqwest/header/struct.HeaderMap.html). */
   withHeaders(headers?: object | undefined | null): this
+  /** Set the cookies to use for the request. A string is used as the Cookie header for the website url. */
+  withCookies(cookies: string | Array<NCookie>): this
+  /** Login before the crawl starts using a HTTP form or headless chrome. The session cookies are used for the crawl and the login runs again when a page redirects back to the login url. The page is then fetched again with the new session and its links are queued. */
+  withLogin(login?: NLoginConfig | undefined | null): this
+  /** Set the HTTP Basic or Digest credentials by origin. The credentials are only sent to the matching origin and never to other domains. */
+  withAuth(auth?: Record<string, NAuth> | undefined | null): this
+  /** Set an async function returning the headers to use for the HTTP requests to the origin of the website. The headers are never sent to other domains. The function is called when the crawl starts and whenever a page returns 401 to refresh expired tokens. */
+  withAuthProvider(provider?: (() => Promise<Record<string, string>>) | undefined | null): this
   /** Add user agent to request. */
   withUserAgent(userAgent?: string | undefined | null): this
+  /** get the robots.txt rules, sitemaps and crawl delays of the website host. The rules are checked for the user agent of the website by default. */
+  getRobots(): Promise<Robots>
   /** Respect robots.txt file. */
   withRespectRobotsTxt(respectRobotsTxt: boolean): this
   /** Determine whether to collect all the resources found on pages. */
   withFullResources(fullResources: boolean): this
   /** Use network interception for the request to only allow content that matches the host. If the content is from a 3rd party it needs to be part of our include list. */
   withChromeIntercept(chromeIntercept: boolean, blockImages: boolean): this
+  /** Block the resources in headless mode by type, url pattern and domain. The page blockedResources field counts the resources referenced by the page html matching the rules. This method does nothing if the `chrome` is not enabled. */
+  withResourceBlocking(blocking?: NResourceBlocking | undefined | null): this
+  /** Normalize the links before they enter the visited set removing the fragments. The page originalUrl and normalizedUrl fields are set and pages with a normalized url already sent are skipped. */
+  withUrlNormalization(normalization?: NUrlNormalization | undefined | null): this
+  /** Skip the page events of the near-duplicate pages using the SimHash of the page text. The page fingerprint field is set and the duplicates are counted in the stats. */
+  withDedupe(dedupe?: NDedupe | undefined | null): this
+  /** Compare the content hash of the pages by url with the last run. The page changeStatus field is set and the pages of the last run not found are sent as removed at the end of the run. Set the path to keep the hashes in a JSON file between processes. */
+  withChangeTracking(changeTracking?: NChangeTracking | undefined | null): this
+  /** Use the chrome instances of the pool for headless crawls. The pool can be shared by many websites and a crawl waits for a free chrome instance. The crawl concurrency is limited to the tabs allowed per chrome instance and the website settings are restored after the crawl. */
+  withBrowserPool(browserPool: BrowserPool): this
   /** Set the connection url for the chrome instance. This method does nothing if the `chrome` is not enabled. */
   withChromeConnection(chromeConnection: string): this
   /** Preserve the HOST header. */
@@ -113,8 +656,20 @@ export class Website {
   withHttp2PriorKnowledge(http2PriorKnowledge: boolean): this
   /** Max time to wait for request duration to milliseconds. */
   withRequestTimeout(requestTimeout?: number | undefined | null): this
+  /** Max time to wait for the entire crawl in milliseconds. The crawl stops cleanly and the status is set to `TimedOut`. */
+  withCrawlTimeout(crawlTimeout?: number | undefined | null): this
+  /** Set the max amount of pages to crawl. The crawl stops cleanly and the status is set to `LimitReached` when pages were left. The limit caps the `*` budget without changing the budget set. If the value is 0 there is no limit. */
+  withLimit(limit: number): this
   /** add external domains */
   withExternalDomains(externalDomains?: Array<string> | undefined | null): this
+  /** Set the browser viewport. This does nothing without chrome. */
+  withViewport(viewport?: NViewport | undefined | null): this
+  /** Set the browser timezone using the IANA name like `America/New_York`. This does nothing without chrome. */
+  withTimezone(timezone?: string | undefined | null): this
+  /** Set the browser locale like `fr-FR`. This does nothing without chrome. */
+  withLocale(locale?: string | undefined | null): this
+  /** Emulate a device preset setting the viewport and user agent: desktop, laptop, iphone-15, iphone-se, ipad, pixel-7 or galaxy-s23. This does nothing without chrome. */
+  withDevice(device: string): this
   /** Use stealth mode for the request. This does nothing without chrome. */
   withStealth(stealthMode?: boolean | undefined | null): this
   /** Dangerously accept invalid certificates - this should be used as a last resort. */
@@ -133,6 +688,10 @@ export class Website {
   withWaitForDelay(seconds?: number | undefined | null, nanos?: number | undefined | null): this
   /** Wait for a CSS query selector. This method does nothing if the `chrome` feature is not enabled. */
   withWaitForSelector(selector?: string | undefined | null, seconds?: number | undefined | null, nanos?: number | undefined | null): this
+  /** Evaluate the javascript expression after page load on the pages matching the url or path pattern in headless mode. Use `/` for every page. The JSON result is set on the page evaluated field. This method does nothing if the `chrome` feature is not enabled. */
+  withEvaluateScripts(scripts?: Record<string, string> | undefined | null): this
+  /** Run the automation steps after navigation on the pages matching the url or path pattern in headless mode. This method does nothing if the `chrome` feature is not enabled. */
+  withAutomationScripts(scripts?: Record<string, Array<NAutomationStep>> | undefined | null): this
   /** Wait for idle network request. This method does nothing if the `chrome` feature is not enabled. */
   withWaitForIdleNetwork(seconds?: number | undefined | null, nanos?: number | undefined | null): this
   /** Setup cron jobs to run */
@@ -174,18 +733,32 @@ export class Website {
   /** The output directory to store the file. Parent folders may be created inside the directory. */
   output_dir: string | null
   }): this
+  /** Render the pages of the headless crawls as PDF using the chrome of the crawl. The bytes are set on the page pdf field. */
+  withPdf(options?: NPdfOptions | undefined | null): this
   /** Delay between request as ms. */
   withDelay(delay: number): this
   /** Set a crawl depth limit. If the value is 0 there is no limit. */
   withDepth(depth: number): this
+  /** Set the amount of retries for the failed requests. The value is capped at 255. */
+  withRetry(retry: number): this
   /** Return the links found on the page in the channel subscriptions. This method does nothing if the `decentralized` is enabled. */
   withReturnPageLinks(returnPageLinks: boolean): this
+  /** Report the urls found that were not crawled with the rule that excluded them using `website.getSkipped`. The links are collected during the crawl without being returned on the pages. */
+  withSkipReport(skipReport: boolean): this
   /** Cache the page following HTTP rules. */
   withCaching(cache: boolean): this
   /** Set the sitemap url. */
   withSitemap(sitemap?: string | undefined | null): this
-  /** Use proxies for request. */
+  /** get the entries of the sitemaps following the sitemap indexes and gzip sitemaps. The sitemap url set with `website.withSitemap` is used first, then the sitemaps listed in robots.txt and /sitemap.xml. */
+  getSitemapEntries(): Promise<Array<NSitemapEntry>>
+  /** Use proxies for request. With 2 or more proxies and the auth, request limits or rotation the proxies are rotated by the HTTP client. */
   withProxies(proxies?: Array<string> | undefined | null): this
+  /** Choose the proxy for every request using a strategy. Proxies failing repeatedly are ejected for a while. The rotation applies to the HTTP requests. */
+  withProxyRotation(rotation?: NProxyRotation | undefined | null): this
+  /** get the health of every proxy used by the rotation during the last crawl. */
+  getProxyStats(): Array<NProxyStats>
+  /** Send the live crawl statistics to the callback on an interval in milliseconds. Defaults to 1000ms. */
+  withOnProgress(onProgress: (err: Error | null, arg: NCrawlStats) => any, interval?: number | undefined | null): this
   /** build the inner website - not required for all builder_steps */
   build(): this
 }
@@ -310,11 +310,16 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { pageTitle, NWebsite, Page, crawl, Website, Cron } = nativeBinding
+const { setLogger, pageTitle, CrawlPool, NWebsite, Page, BrowserPool, Robots, crawl, Website, Cron } = nativeBinding
+
+module.exports.setLogger = setLogger
 
 module.exports.pageTitle = pageTitle
+module.exports.CrawlPool = CrawlPool
 module.exports.NWebsite = NWebsite
 module.exports.Page = Page
+module.exports.BrowserPool = BrowserPool
+module.exports.Robots = Robots
 module.exports.crawl = crawl
 module.exports.Website = Website
 module.exports.Cron = Cron
@@ -0,0 +1,497 @@
+use md5::Md5;
+use napi::bindgen_prelude::Promise;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use spider::reqwest::header::{
+  HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE,
+};
+use spider::reqwest::{Request, Response, StatusCode};
+use spider::reqwest_middleware::{Middleware, Next};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// the username and password for an origin.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NAuthCredentials {
+  /// The username.
+  pub username: String,
+  /// The password.
+  pub password: String,
+}
+
+/// the credentials used for an origin.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NAuth {
+  /// Send the credentials using HTTP Basic auth with every request to the origin.
+  pub basic: Option<NAuthCredentials>,
+  /// Answer HTTP Digest challenges from the origin.
+  pub digest: Option<NAuthCredentials>,
+}
+
+/// a digest challenge from the WWW-Authenticate header.
+#[derive(Default, Clone)]
+struct DigestChallenge {
+  /// the protection space.
+  realm: String,
+  /// the server nonce.
+  nonce: String,
+  /// the opaque value to return as is.
+  opaque: Option<String>,
+  /// the quality of protection.
+  qop: Option<String>,
+  /// the hash algorithm.
+  algorithm: String,
+  /// the amount of requests made with the nonce.
+  nc: u32,
+}
+
+impl DigestChallenge {
+  /// parse the digest challenge from a WWW-Authenticate header value.
+  fn parse(header: &str) -> Option<DigestChallenge> {
+    let header = header.trim();
+
+    if !header
+      .get(..7)
+      .is_some_and(|scheme| scheme.eq_ignore_ascii_case("digest "))
+    {
+      return None;
+    }
+
+    let mut challenge = DigestChallenge {
+      algorithm: "MD5".into(),
+      ..Default::default()
+    };
+
+    for (key, value) in parse_params(&header[7..]) {
+      match key.to_lowercase().as_str() {
+        "realm" => challenge.realm = value,
+        "nonce" => challenge.nonce = value,
+        "opaque" => challenge.opaque = Some(value),
+        "algorithm" => challenge.algorithm = value.to_uppercase(),
+        "qop"
+          // prefer auth over auth-int since the body is not hashed.
+          if value.split(',').any(|qop| qop.trim() == "auth") => {
+            challenge.qop = Some("auth".into())
+          }
+        _ => (),
+      }
+    }
+
+    if challenge.nonce.is_empty() {
+      None
+    } else {
+      Some(challenge)
+    }
+  }
+
+  /// hash the value using the challenge algorithm.
+  fn hash(&self, value: &str) -> String {
+    if self.algorithm.starts_with("SHA-256") {
+      to_hex(&Sha256::digest(value.as_bytes()))
+    } else {
+      to_hex(&Md5::digest(value.as_bytes()))
+    }
+  }
+
+  /// the Authorization header value answering the challenge for the request.
+  fn authorization(&mut self, credentials: &NAuthCredentials, method: &str, uri: &str) -> String {
+    self.nc += 1;
+
+    let nc = format!("{:08x}", self.nc);
+    let cnonce = format!(
+      "{:016x}",
+      SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+        ^ self.nc as u64
+    );
+
+    let mut ha1 = self.hash(&format!(
+      "{}:{}:{}",
+      credentials.username, self.realm, credentials.password
+    ));
+
+    if self.algorithm.ends_with("-SESS") {
+      ha1 = self.hash(&format!("{}:{}:{}", ha1, self.nonce, cnonce));
+    }
+
+    let ha2 = self.hash(&format!("{}:{}", method, uri));
+
+    let response = match self.qop {
+      Some(ref qop) => self.hash(&format!(
+        "{}:{}:{}:{}:{}:{}",
+        ha1, self.nonce, nc, cnonce, qop, ha2
+      )),
+      _ => self.hash(&format!("{}:{}:{}", ha1, self.nonce, ha2)),
+    };
+
+    let mut header = format!(
+      "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+      credentials.username, self.realm, self.nonce, uri, self.algorithm, response
+    );
+
+    if let Some(ref qop) = self.qop {
+      header.push_str(&format!(", qop={}, nc={}, cnonce=\"{}\"", qop, nc, cnonce));
+    }
+    if let Some(ref opaque) = self.opaque {
+      header.push_str(&format!(", opaque=\"{}\"", opaque));
+    }
+
+    header
+  }
+}
+
+/// parse the comma separated key=value parameters of a challenge.
+fn parse_params(params: &str) -> Vec<(String, String)> {
+  let mut parsed = Vec::new();
+  let mut chars = params.chars().peekable();
+
+  loop {
+    let key = chars
+      .by_ref()
+      .skip_while(|c| c.is_whitespace() || *c == ',')
+      .take_while(|c| *c != '=')
+      .collect::<String>();
+
+    if key.is_empty() {
+      break;
+    }
+
+    let mut value = String::new();
+
+    if chars.peek() == Some(&'"') {
+      chars.next();
+      while let Some(c) = chars.next() {
+        match c {
+          '\\' => value.extend(chars.next()),
+          '"' => break,
+          _ => value.push(c),
+        }
+      }
+    } else {
+      while let Some(c) = chars.next_if(|c| *c != ',') {
+        value.push(c);
+      }
+    }
+
+    parsed.push((key.trim().to_string(), value.trim().to_string()));
+  }
+
+  parsed
+}
+
+/// encode the bytes as lowercase hex.
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// the credentials by origin with the digest challenges received.
+#[derive(Default)]
+pub struct OriginAuth {
+  /// the credentials by origin.
+  credentials: RwLock<HashMap<String, NAuth>>,
+  /// the last digest challenge by origin.
+  challenges: Mutex<HashMap<String, DigestChallenge>>,
+}
+
+impl OriginAuth {
+  /// set the credentials by origin. Urls are reduced to their origin.
+  pub fn set_credentials(&self, auth: Option<HashMap<String, NAuth>>) {
+    let mut credentials = HashMap::new();
+
+    for (url, auth) in auth.unwrap_or_default() {
+      match spider::url::Url::parse(&url) {
+        Ok(url) => {
+          credentials.insert(url.origin().ascii_serialization(), auth);
+        }
+        Err(e) => log::warn!("invalid auth origin {url}: {e}"),
+      }
+    }
+
+    if let Ok(mut c) = self.credentials.write() {
+      *c = credentials;
+    }
+    if let Ok(mut challenges) = self.challenges.lock() {
+      challenges.clear();
+    }
+  }
+
+  /// the credentials by origin.
+  pub fn get_credentials(&self) -> Option<HashMap<String, NAuth>> {
+    match self.credentials.read() {
+      Ok(credentials) if !credentials.is_empty() => Some(credentials.clone()),
+      _ => None,
+    }
+  }
+
+  /// are credentials configured.
+  pub fn is_enabled(&self) -> bool {
+    match self.credentials.read() {
+      Ok(credentials) => !credentials.is_empty(),
+      _ => false,
+    }
+  }
+
+  /// get the credentials for the origin.
+  fn get(&self, origin: &str) -> Option<NAuth> {
+    match self.credentials.read() {
+      Ok(credentials) => credentials.get(origin).cloned(),
+      _ => None,
+    }
+  }
+
+  /// answer the digest challenge of the origin for the request.
+  fn digest_header(
+    &self,
+    origin: &str,
+    credentials: &NAuthCredentials,
+    req: &Request,
+  ) -> Option<HeaderValue> {
+    let mut challenges = self.challenges.lock().ok()?;
+    let challenge = challenges.get_mut(origin)?;
+    let uri = match req.url().query() {
+      Some(query) => format!("{}?{}", req.url().path(), query),
+      _ => req.url().path().to_string(),
+    };
+
+    HeaderValue::from_str(&challenge.authorization(credentials, req.method().as_str(), &uri)).ok()
+  }
+}
+
+/// the headers returned by the javascript auth provider.
+#[derive(Default)]
+pub struct AuthProvider {
+  /// the javascript callback returning the headers.
+  callback: RwLock<Option<ThreadsafeFunction<(), ErrorStrategy::Fatal>>>,
+  /// the last headers returned.
+  headers: RwLock<HeaderMap>,
+  /// the origin of the website receiving the headers.
+  origin: RwLock<Option<String>>,
+  /// the amount of times the headers were refreshed.
+  generation: AtomicU64,
+  /// a single refresh runs at a time.
+  refreshing: spider::tokio::sync::Mutex<()>,
+}
+
+impl AuthProvider {
+  /// set the javascript callback.
+  pub fn set_callback(&self, callback: Option<ThreadsafeFunction<(), ErrorStrategy::Fatal>>) {
+    if let Ok(mut c) = self.callback.write() {
+      *c = callback;
+    }
+    if let Ok(mut headers) = self.headers.write() {
+      headers.clear();
+    }
+  }
+
+  /// the javascript callback.
+  pub fn get_callback(&self) -> Option<ThreadsafeFunction<(), ErrorStrategy::Fatal>> {
+    match self.callback.read() {
+      Ok(callback) => callback.clone(),
+      _ => None,
+    }
+  }
+
+  /// is a provider configured.
+  pub fn is_enabled(&self) -> bool {
+    match self.callback.read() {
+      Ok(callback) => callback.is_some(),
+      _ => false,
+    }
+  }
+
+  /// set the origin receiving the headers from the website url.
+  pub fn set_origin(&self, url: &str) {
+    if let Ok(mut origin) = self.origin.write() {
+      *origin = spider::url::Url::parse(url)
+        .ok()
+        .map(|url| url.origin().ascii_serialization());
+    }
+  }
+
+  /// call the provider for new headers.
+  pub async fn refresh(&self) -> Result<(), String> {
+    let callback = match self.callback.read() {
+      Ok(callback) => callback.clone(),
+      _ => None,
+    };
+
+    let callback = match callback {
+      Some(callback) => callback,
+      _ => return Ok(()),
+    };
+
+    let headers = callback
+      .call_async::<Promise<HashMap<String, String>>>(())
+      .await
+      .map_err(|e| e.to_string())?
+      .await
+      .map_err(|e| e.to_string())?;
+
+    let mut header_map = HeaderMap::new();
+
+    for (key, value) in headers {
+      match (
+        HeaderName::from_bytes(key.as_bytes()),
+        HeaderValue::from_str(&value),
+      ) {
+        (Ok(key), Ok(value)) => {
+          header_map.insert(key, value);
+        }
+        _ => log::warn!("invalid auth provider header {key}"),
+      }
+    }
+
+    if let Ok(mut headers) = self.headers.write() {
+      *headers = header_map;
+    }
+    self.generation.fetch_add(1, Ordering::Relaxed);
+
+    Ok(())
+  }
+
+  /// refresh the headers after a 401 unless another request already refreshed them.
+  async fn refresh_after(&self, generation: u64) {
+    let _lock = self.refreshing.lock().await;
+
+    if self.generation.load(Ordering::Relaxed) == generation {
+      if let Err(e) = self.refresh().await {
+        log::warn!("auth provider failed: {e}");
+      }
+    }
+  }
+
+  /// is the request sent to the origin of the website.
+  fn is_origin(&self, req: &Request) -> bool {
+    match self.origin.read() {
+      Ok(origin) => origin.as_deref() == Some(&req.url().origin().ascii_serialization()),
+      _ => false,
+    }
+  }
+
+  /// add the last headers returned to the request.
+  fn apply(&self, req: &mut Request) {
+    if let Ok(headers) = self.headers.read() {
+      for (key, value) in headers.iter() {
+        req.headers_mut().insert(key, value.clone());
+      }
+    }
+  }
+}
+
+/// the client middleware adding the credentials to the requests of the configured origins only.
+pub struct AuthMiddleware {
+  /// the credentials by origin.
+  pub origins: Arc<OriginAuth>,
+  /// the headers returned by the auth provider.
+  pub provider: Arc<AuthProvider>,
+}
+
+impl AuthMiddleware {
+  /// send the request with the credentials of the origin.
+  async fn send(
+    &self,
+    mut req: Request,
+    extensions: &mut http::Extensions,
+    next: Next<'_>,
+  ) -> spider::reqwest_middleware::Result<Response> {
+    let origin = req.url().origin().ascii_serialization();
+
+    let auth = match self.origins.get(&origin) {
+      Some(auth) => auth,
+      _ => return next.run(req, extensions).await,
+    };
+
+    if let Some(basic) = auth.basic {
+      use base64::Engine;
+
+      let token = base64::engine::general_purpose::STANDARD
+        .encode(format!("{}:{}", basic.username, basic.password));
+
+      if let Ok(value) = HeaderValue::from_str(&format!("Basic {}", token)) {
+        req.headers_mut().insert(AUTHORIZATION, value);
+      }
+
+      return next.run(req, extensions).await;
+    }
+
+    let digest = match auth.digest {
+      Some(digest) => digest,
+      _ => return next.run(req, extensions).await,
+    };
+
+    // reuse the last challenge to skip the 401 round trip.
+    if let Some(value) = self.origins.digest_header(&origin, &digest, &req) {
+      req.headers_mut().insert(AUTHORIZATION, value);
+    }
+
+    let retry = req.try_clone();
+    let res = next.clone().run(req, extensions).await?;
+
+    if res.status() != StatusCode::UNAUTHORIZED {
+      return Ok(res);
+    }
+
+    let challenge = res
+      .headers()
+      .get_all(WWW_AUTHENTICATE)
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .find_map(DigestChallenge::parse);
+
+    match (challenge, retry) {
+      (Some(challenge), Some(mut retry)) => {
+        if let Ok(mut challenges) = self.origins.challenges.lock() {
+          challenges.insert(origin.clone(), challenge);
+        }
+        match self.origins.digest_header(&origin, &digest, &retry) {
+          Some(value) => {
+            retry.headers_mut().insert(AUTHORIZATION, value);
+            next.run(retry, extensions).await
+          }
+          _ => Ok(res),
+        }
+      }
+      _ => Ok(res),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl Middleware for AuthMiddleware {
+  async fn handle(
+    &self,
+    mut req: Request,
+    extensions: &mut http::Extensions,
+    next: Next<'_>,
+  ) -> spider::reqwest_middleware::Result<Response> {
+    // the provider headers are only sent to the origin of the website.
+    if !self.provider.is_enabled() || !self.provider.is_origin(&req) {
+      return self.send(req, extensions, next).await;
+    }
+
+    self.provider.apply(&mut req);
+
+    let generation = self.provider.generation.load(Ordering::Relaxed);
+    let retry = req.try_clone();
+    let res = self.send(req, extensions, next.clone()).await?;
+
+    match retry {
+      Some(mut retry) if res.status() == StatusCode::UNAUTHORIZED => {
+        // the token expired, refresh the headers and send the request again.
+        self.provider.refresh_after(generation).await;
+        self.provider.apply(&mut retry);
+        self.send(retry, extensions, next).await
+      }
+      _ => Ok(res),
+    }
+  }
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use spider::features::chrome_common::{AutomationScriptsMap, WebAutomation};
+use std::collections::HashMap;
+
+/// an input to fill.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NFillStep {
+  /// The CSS selector of the input.
+  pub selector: String,
+  /// The value to type.
+  pub value: String,
+}
+
+/// an action to run on the page after navigation. Set one action per step.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NAutomationStep {
+  /// Click on the element matching the CSS selector.
+  pub click: Option<String>,
+  /// Click on all of the elements matching the CSS selector.
+  pub click_all: Option<String>,
+  /// Scroll vertically by the amount of pixels.
+  pub scroll: Option<i32>,
+  /// Scroll horizontally by the amount of pixels.
+  pub scroll_x: Option<i32>,
+  /// Scroll to the bottom of the page the amount of times to load more content.
+  pub infinite_scroll: Option<u32>,
+  /// Fill the input with the value.
+  pub fill: Option<NFillStep>,
+  /// Wait for the amount of milliseconds.
+  pub wait: Option<u32>,
+  /// Wait for the element matching the CSS selector.
+  pub wait_for: Option<String>,
+  /// Wait for the next navigation.
+  pub wait_for_navigation: Option<bool>,
+  /// Run the javascript on the page.
+  pub evaluate: Option<String>,
+}
+
+impl NAutomationStep {
+  /// the chrome actions of the step.
+  pub fn actions(self) -> Vec<WebAutomation> {
+    let mut actions = Vec::new();
+
+    if let Some(selector) = self.wait_for {
+      actions.push(WebAutomation::WaitFor(selector));
+    }
+    if let Some(selector) = self.click {
+      actions.push(WebAutomation::Click(selector));
+    }
+    if let Some(selector) = self.click_all {
+      actions.push(WebAutomation::ClickAll(selector));
+    }
+    if let Some(fill) = self.fill {
+      actions.push(WebAutomation::Fill {
+        selector: fill.selector,
+        value: fill.value,
+      });
+    }
+    if let Some(pixels) = self.scroll {
+      actions.push(WebAutomation::ScrollY(pixels));
+    }
+    if let Some(pixels) = self.scroll_x {
+      actions.push(WebAutomation::ScrollX(pixels));
+    }
+    if let Some(times) = self.infinite_scroll {
+      actions.push(WebAutomation::InfiniteScroll(times));
+    }
+    if let Some(script) = self.evaluate {
+      actions.push(WebAutomation::Evaluate(script));
+    }
+    if self.wait_for_navigation.unwrap_or_default() {
+      actions.push(WebAutomation::WaitForNavigation);
+    }
+    if let Some(ms) = self.wait {
+      actions.push(WebAutomation::Wait(ms.into()));
+    }
+
+    actions
+  }
+}
+
+/// convert the steps by url pattern to the chrome automation scripts.
+pub fn to_automation_scripts(
+  scripts: HashMap<String, Vec<NAutomationStep>>,
+) -> AutomationScriptsMap {
+  scripts
+    .into_iter()
+    .map(|(pattern, steps)| {
+      (
+        pattern,
+        steps
+          .into_iter()
+          .flat_map(NAutomationStep::actions)
+          .collect(),
+      )
+    })
+    .collect()
+}
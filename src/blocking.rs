@@ -0,0 +1,209 @@
+use crate::options::Options;
+use serde::{Deserialize, Serialize};
+use spider::features::chrome_common::RequestInterceptConfiguration;
+use spider::lazy_static::lazy_static;
+use std::sync::Mutex;
+
+lazy_static! {
+  static ref RESOURCE_SELECTOR: scraper::Selector = scraper::Selector::parse(
+    "img[src], source[src], video[src], audio[src], script[src], link[rel~=stylesheet][href], link[rel~=preload][href], link[rel~=icon][href]"
+  )
+  .unwrap();
+}
+
+/// the url patterns blocking the resource types chrome groups together.
+const IMAGE_PATTERNS: &[&str] = &[
+  ".png", ".jpg", ".jpeg", ".gif", ".webp", ".avif", ".svg", ".ico", ".bmp",
+];
+/// the url patterns of the font files.
+const FONT_PATTERNS: &[&str] = &[".woff2", ".woff", ".ttf", ".otf", ".eot"];
+/// the url patterns of the media files.
+const MEDIA_PATTERNS: &[&str] = &[
+  ".mp4", ".webm", ".mov", ".m3u8", ".mp3", ".ogg", ".wav", ".m4a",
+];
+
+/// the resources to block in headless mode.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NResourceBlocking {
+  /// The resource types to block.
+  #[napi(ts_type = "Array<'image' | 'stylesheet' | 'font' | 'media' | 'script'>")]
+  pub resource_types: Option<Vec<String>>,
+  /// Block the requests with a url containing one of the patterns.
+  pub url_patterns: Option<Vec<String>>,
+  /// Block the requests to the domains and their subdomains like third party trackers and ads.
+  pub domains: Option<Vec<String>>,
+}
+
+/// the resource blocking rules for chrome interception.
+#[derive(Default)]
+pub struct ResourceBlocking {
+  /// the blocking options.
+  pub options: Options<NResourceBlocking>,
+  /// the chrome interception of the website replaced by the blocking rules.
+  replaced: Mutex<Option<RequestInterceptConfiguration>>,
+}
+
+impl ResourceBlocking {
+  /// set the blocking options replacing the chrome interception of the website until the blocking is removed.
+  pub fn set_options(
+    &self,
+    website: &mut spider::website::Website,
+    options: Option<NResourceBlocking>,
+  ) {
+    self.options.set(options);
+
+    if let Ok(mut replaced) = self.replaced.lock() {
+      match self.intercept_config() {
+        Some(config) => {
+          if replaced.is_none() {
+            *replaced = Some(website.configuration.chrome_intercept.clone());
+          }
+          website.with_chrome_intercept(config);
+        }
+        _ => {
+          if let Some(previous) = replaced.take() {
+            website.with_chrome_intercept(previous);
+          }
+        }
+      }
+    }
+  }
+
+  /// set the chrome interception of the website. The interception is restored when the blocking is removed while the blocking is set.
+  pub fn set_chrome_intercept(
+    &self,
+    website: &mut spider::website::Website,
+    config: RequestInterceptConfiguration,
+  ) {
+    match self.replaced.lock() {
+      Ok(mut replaced) if replaced.is_some() => *replaced = Some(config),
+      _ => {
+        website.with_chrome_intercept(config);
+      }
+    }
+  }
+
+  /// the chrome interception of the website without the blocking rules.
+  pub fn chrome_intercept(
+    &self,
+    website: &spider::website::Website,
+  ) -> RequestInterceptConfiguration {
+    match self.replaced.lock() {
+      Ok(replaced) if replaced.is_some() => replaced.clone().unwrap_or_default(),
+      _ => website.configuration.chrome_intercept.clone(),
+    }
+  }
+
+  /// the interception configuration of the blocking options.
+  fn intercept_config(&self) -> Option<RequestInterceptConfiguration> {
+    self.options.read(|options| {
+      options.map(|options| {
+        let types = options.resource_types.clone().unwrap_or_default();
+        let has_type = |t: &str| types.iter().any(|resource_type| resource_type == t);
+
+        let mut config = RequestInterceptConfiguration {
+          enabled: true,
+          block_stylesheets: has_type("stylesheet"),
+          block_javascript: has_type("script"),
+          allow_first_party_stylesheets: !has_type("stylesheet"),
+          allow_first_party_javascript: !has_type("script"),
+          ..Default::default()
+        };
+
+        let mut patterns = blocked_patterns(options);
+
+        // chrome matches the patterns as url substrings so the domains are anchored on the host.
+        for domain in options.domains.iter().flatten() {
+          let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+
+          patterns.extend([
+            format!("://{domain}/"),
+            format!("://{domain}:"),
+            format!(".{domain}/"),
+            format!(".{domain}:"),
+          ]);
+        }
+
+        config.set_blacklist_patterns(Some(patterns));
+        config
+      })
+    })
+  }
+
+  /// count the resources referenced by the page html that match the blocking rules.
+  pub fn count_blocked(&self, html: &str, page_url: &str) -> Option<u32> {
+    let options = self.options.get()?;
+
+    let types = options.resource_types.clone().unwrap_or_default();
+    let patterns = blocked_patterns(&options);
+    let base = spider::url::Url::parse(page_url).ok();
+    let fragment = scraper::Html::parse_document(html);
+    let mut blocked = 0;
+
+    for element in fragment.select(&RESOURCE_SELECTOR) {
+      let element = element.value();
+      let resource_type = match element.name() {
+        "script" => "script",
+        "link"
+          if element
+            .attr("rel")
+            .is_some_and(|rel| rel.contains("stylesheet")) =>
+        {
+          "stylesheet"
+        }
+        _ => "",
+      };
+      let src = match element.attr("src").or_else(|| element.attr("href")) {
+        Some(src) if !src.starts_with("data:") => src,
+        _ => continue,
+      };
+      let url = match base.as_ref().and_then(|base| base.join(src).ok()) {
+        Some(url) => url,
+        _ => continue,
+      };
+      let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+
+      if types.iter().any(|t| t == resource_type)
+        || patterns.iter().any(|p| url.as_str().contains(p))
+        || options
+          .domains
+          .iter()
+          .flatten()
+          .any(|domain| is_domain_host(&host, domain))
+      {
+        blocked += 1;
+      }
+    }
+
+    Some(blocked)
+  }
+}
+
+/// the url patterns blocked by the options.
+fn blocked_patterns(options: &NResourceBlocking) -> Vec<String> {
+  let mut patterns = options.url_patterns.clone().unwrap_or_default();
+
+  for resource_type in options.resource_types.iter().flatten() {
+    let type_patterns = match resource_type.as_str() {
+      "image" => IMAGE_PATTERNS,
+      "font" => FONT_PATTERNS,
+      "media" => MEDIA_PATTERNS,
+      _ => &[],
+    };
+    patterns.extend(type_patterns.iter().map(|p| p.to_string()));
+  }
+
+  patterns
+}
+
+/// is the host the domain or one of its subdomains.
+fn is_domain_host(host: &str, domain: &str) -> bool {
+  let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+
+  host == domain
+    || host
+      .strip_suffix(domain.as_str())
+      .is_some_and(|prefix| prefix.ends_with('.'))
+}
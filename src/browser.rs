@@ -0,0 +1,107 @@
+use spider::chromiumoxide::{Browser, BrowserConfig, Handler};
+use spider::tokio::task::JoinHandle;
+use spider::tokio_stream::StreamExt;
+
+/// a chrome instance used outside of the crawl with its event handler.
+pub struct BrowserHandle {
+  /// the chrome browser.
+  pub browser: Browser,
+  /// the browser event handler task.
+  handle: JoinHandle<()>,
+  /// was the browser launched by the handle.
+  launched: bool,
+}
+
+impl BrowserHandle {
+  /// connect to the chrome instance using the connection url or the `CHROME_URL` env variable and launch chrome as a fallback.
+  pub async fn connect(chrome_connection: Option<&str>) -> Result<BrowserHandle, String> {
+    let chrome_url = match chrome_connection {
+      Some(url) => Some(url.to_string()),
+      _ => std::env::var("CHROME_URL").ok(),
+    };
+
+    match chrome_url {
+      Some(url) => {
+        let (browser, handler) = Browser::connect(url).await.map_err(|e| e.to_string())?;
+
+        Ok(BrowserHandle {
+          browser,
+          handle: spawn_handler(handler),
+          launched: false,
+        })
+      }
+      _ => BrowserHandle::launch().await,
+    }
+  }
+
+  /// launch a new local chrome instance.
+  pub async fn launch() -> Result<BrowserHandle, String> {
+    let (browser, handler) = Browser::launch(BrowserConfig::builder().build()?)
+      .await
+      .map_err(|e| e.to_string())?;
+
+    Ok(BrowserHandle {
+      browser,
+      handle: spawn_handler(handler),
+      launched: true,
+    })
+  }
+
+  /// the websocket url to connect to the browser.
+  pub fn websocket_address(&self) -> String {
+    self.browser.websocket_address().clone()
+  }
+
+  /// is the browser connection still open.
+  pub fn is_running(&self) -> bool {
+    !self.handle.is_finished()
+  }
+
+  /// close the browser when launched by the handle and stop the event handler.
+  pub async fn close(mut self) {
+    if self.launched {
+      let _ = self.browser.close().await;
+      let _ = self.browser.wait().await;
+    }
+    self.handle.abort();
+  }
+}
+
+/// drive the browser events until the connection closes.
+fn spawn_handler(mut handler: Handler) -> JoinHandle<()> {
+  spider::tokio::spawn(async move {
+    while let Some(event) = handler.next().await {
+      if event.is_err() {
+        break;
+      }
+    }
+  })
+}
+
+/// the chrome settings of a website replaced during a crawl by the browser pool or the PDF renders.
+pub struct ChromeSettings {
+  /// the chrome connection url.
+  pub chrome_connection_url: Option<String>,
+  /// the crawl concurrency.
+  pub concurrency_limit: Option<usize>,
+}
+
+impl ChromeSettings {
+  /// save the chrome settings of the website.
+  pub fn save(website: &spider::website::Website) -> ChromeSettings {
+    ChromeSettings {
+      chrome_connection_url: website.configuration.chrome_connection_url.clone(),
+      concurrency_limit: website.configuration.concurrency_limit,
+    }
+  }
+
+  /// restore the chrome settings of the website.
+  pub fn restore(self, website: &mut spider::website::Website) {
+    website
+      .configuration
+      .with_chrome_connection(self.chrome_connection_url);
+    website
+      .configuration
+      .with_concurrency_limit(self.concurrency_limit);
+  }
+}
@@ -0,0 +1,214 @@
+use crate::options::Options;
+use crate::NPage;
+use indexmap::IndexMap;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// the change detection options.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NChangeTracking {
+  /// The JSON file storing the content hashes by url between runs. The hashes are kept in memory for the website when not set.
+  pub path: Option<String>,
+}
+
+/// the content hashes by url compared between crawl runs.
+#[derive(Default)]
+pub struct ChangeStore {
+  /// the change detection options.
+  pub options: Options<NChangeTracking>,
+  /// the hashes saved at the end of the last run when the store file is not set.
+  stored: Mutex<HashMap<String, String>>,
+  /// the hashes of the last run.
+  previous: Mutex<HashMap<String, String>>,
+  /// the hashes of the current run.
+  current: Mutex<IndexMap<String, String>>,
+  /// the urls of the last run not found in the current run.
+  removed: Mutex<Vec<String>>,
+  /// the callback receiving the removed pages at the end of a run.
+  callback: Mutex<Option<ThreadsafeFunction<NPage>>>,
+}
+
+impl ChangeStore {
+  /// set the callback receiving the removed pages.
+  pub fn set_callback(&self, callback: Option<ThreadsafeFunction<NPage>>) {
+    if let Ok(mut c) = self.callback.lock() {
+      *c = callback;
+    }
+  }
+
+  /// load the hashes of the last run before a new run. The pages hashed after the last run ended are kept.
+  pub fn start(&self) {
+    if !self.options.is_enabled() {
+      return;
+    }
+
+    let mut hashes = self.load();
+
+    if let Ok(mut current) = self.current.lock() {
+      hashes.extend(current.drain(..));
+    }
+    if let Ok(mut previous) = self.previous.lock() {
+      *previous = hashes;
+    }
+    if let Ok(mut removed) = self.removed.lock() {
+      removed.clear();
+    }
+  }
+
+  /// the change status of the page compared to the last run.
+  pub fn status(&self, url: &str, status_code: u16, html: &[u8]) -> Option<String> {
+    // the failed fetches are not hashed.
+    if !self.options.is_enabled() || status_code == 0 || status_code >= 500 {
+      return None;
+    }
+
+    let previous = match self.previous.lock() {
+      Ok(previous) => previous.get(url).cloned(),
+      _ => None,
+    };
+
+    // a missing page never crawled before has no change.
+    if status_code == 404 || status_code == 410 {
+      return previous.map(|_| "removed".into());
+    }
+
+    // the page is hashed once for the stats listener and the page callback.
+    let hash = match self.current.lock() {
+      Ok(current) => current.get(url).cloned(),
+      _ => None,
+    };
+    let hash = match hash {
+      Some(hash) => hash,
+      _ => {
+        let hash = format!("{:x}", Sha256::digest(html));
+
+        if let Ok(mut current) = self.current.lock() {
+          current.insert(url.to_string(), hash.clone());
+        }
+
+        hash
+      }
+    };
+
+    let status = match previous {
+      Some(previous) if previous == hash => "unchanged",
+      Some(_) => "changed",
+      _ => "new",
+    };
+
+    Some(status.into())
+  }
+
+  /// save the hashes and send the urls of the last run that were not crawled as removed pages. A run without pages hashed keeps the stored hashes.
+  pub fn finish(&self, website: &spider::website::Website) {
+    if !self.options.is_enabled() {
+      return;
+    }
+
+    let visited: HashSet<String> = website
+      .get_links()
+      .iter()
+      .map(|link| link.as_ref().to_string())
+      .collect();
+    let current = match self.current.lock() {
+      Ok(current) => current.clone(),
+      _ => Default::default(),
+    };
+    let (removed, stored): (Vec<String>, bool) = match self.previous.lock() {
+      Ok(previous) => (
+        previous
+          .keys()
+          .filter(|url| !current.contains_key(*url) && !visited.contains(*url))
+          .cloned()
+          .collect(),
+        !previous.is_empty(),
+      ),
+      _ => Default::default(),
+    };
+
+    // keep the hashes of the last run when no page was hashed like an unreachable site.
+    if current.is_empty() && stored {
+      log::warn!("change store not saved, no page was hashed");
+    } else {
+      self.save(&current);
+    }
+
+    if let Ok(callback) = self.callback.lock() {
+      if let Some(ref callback) = *callback {
+        for url in removed.iter() {
+          let page = NPage {
+            url: url.clone(),
+            change_status: Some("removed".into()),
+            ..Default::default()
+          };
+          callback.call(Ok(page), ThreadsafeFunctionCallMode::NonBlocking);
+        }
+      }
+    }
+
+    if let Ok(mut r) = self.removed.lock() {
+      *r = removed;
+    }
+  }
+
+  /// the urls of the last run that were not found in the current run.
+  pub fn removed(&self) -> Vec<String> {
+    match self.removed.lock() {
+      Ok(removed) => removed.clone(),
+      _ => Default::default(),
+    }
+  }
+
+  /// the path of the store file.
+  fn path(&self) -> Option<String> {
+    self.options.get().and_then(|options| options.path)
+  }
+
+  /// read the hashes from the store file.
+  fn load(&self) -> HashMap<String, String> {
+    let path = match self.path() {
+      Some(path) => path,
+      _ => {
+        return match self.stored.lock() {
+          Ok(stored) => stored.clone(),
+          _ => Default::default(),
+        }
+      }
+    };
+
+    match std::fs::read(&path) {
+      Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+        log::warn!("change store {path} is invalid: {e}");
+        Default::default()
+      }),
+      _ => Default::default(),
+    }
+  }
+
+  /// write the hashes to the store file replacing it once written.
+  fn save(&self, hashes: &IndexMap<String, String>) {
+    let path = match self.path() {
+      Some(path) => path,
+      _ => {
+        if let Ok(mut stored) = self.stored.lock() {
+          *stored = hashes.clone().into_iter().collect();
+        }
+        return;
+      }
+    };
+    let tmp = format!("{path}.tmp");
+    let written = serde_json::to_vec(hashes)
+      .map_err(|e| e.to_string())
+      .and_then(|bytes| std::fs::write(&tmp, bytes).map_err(|e| e.to_string()))
+      .and_then(|_| std::fs::rename(&tmp, &path).map_err(|e| e.to_string()));
+
+    if let Err(e) = written {
+      log::warn!("change store {path} failed to save: {e}");
+    }
+  }
+}
@@ -0,0 +1,111 @@
+use crate::auth::{AuthMiddleware, AuthProvider, OriginAuth};
+use crate::changes::ChangeStore;
+use crate::crawl_pool::{LimitMiddleware, RequestLimits};
+use crate::proxy::{ProxyMiddleware, ProxyRotation};
+use crate::stats::CrawlStats;
+use spider::async_job::Job;
+use std::sync::Arc;
+
+/// the middleware shared by the crawl clients.
+#[derive(Default, Clone)]
+pub struct ClientLayers {
+  /// the credentials by origin.
+  pub auth: Arc<OriginAuth>,
+  /// the headers returned by the javascript auth provider.
+  pub auth_provider: Arc<AuthProvider>,
+  /// the proxies rotated for the requests.
+  pub proxies: Arc<ProxyRotation>,
+  /// the request limits shared with the other sites of a crawl pool.
+  pub limits: Arc<RequestLimits>,
+}
+
+impl ClientLayers {
+  /// is any middleware enabled.
+  pub fn is_enabled(&self) -> bool {
+    self.auth.is_enabled()
+      || self.auth_provider.is_enabled()
+      || self.proxies.options.is_enabled()
+      || self.limits.is_enabled()
+  }
+
+  /// are the proxies rotated by the middleware. spider sends the requests of 2 or more proxies with its own clients skipping the middleware.
+  fn rotates_proxies(&self) -> bool {
+    self.proxies.options.is_enabled()
+      || (self.proxies.get_proxies().len() > 1
+        && (self.auth.is_enabled() || self.auth_provider.is_enabled() || self.limits.is_enabled()))
+  }
+
+  /// refresh the auth provider headers and set the HTTP client of the website before a run.
+  pub async fn setup(&self, website: &mut spider::website::Website) -> Result<(), String> {
+    if self.auth_provider.is_enabled() {
+      self.auth_provider.set_origin(website.get_url().inner());
+      self.auth_provider.refresh().await?;
+    }
+
+    let rotate = self.rotates_proxies();
+    let proxies = self.proxies.get_proxies();
+
+    website
+      .configuration
+      .with_proxies(if rotate || proxies.is_empty() {
+        None
+      } else {
+        Some(proxies)
+      });
+
+    if rotate {
+      self.proxies.prepare(website);
+    } else {
+      self.proxies.clear();
+    }
+
+    if self.is_enabled() {
+      let client = website
+        .configure_http_client_builder()
+        // the slots are held across the auth and proxy retries.
+        .with(LimitMiddleware(self.limits.clone()))
+        // the credentials are only sent to the configured origins.
+        .with(AuthMiddleware {
+          origins: self.auth.clone(),
+          provider: self.auth_provider.clone(),
+        })
+        // the proxy clients send the requests without running the next layers so it stays last.
+        .with(ProxyMiddleware(self.proxies.clone()))
+        .build();
+
+      website.set_http_client(client);
+    }
+
+    Ok(())
+  }
+}
+
+/// the cron job setting up the client before every run.
+pub struct CronJob {
+  /// the website to crawl.
+  pub website: spider::website::Website,
+  /// the middleware for the client.
+  pub layers: ClientLayers,
+  /// the content hashes compared between runs.
+  pub changes: Arc<ChangeStore>,
+  /// the stats listener hashing the pages of the runs.
+  pub crawl_stats: Arc<CrawlStats>,
+}
+
+#[spider::async_job::async_trait]
+impl Job for CronJob {
+  fn schedule(&self) -> Option<spider::async_job::Schedule> {
+    self.website.schedule()
+  }
+
+  async fn handle(&mut self) {
+    if let Err(e) = self.layers.setup(&mut self.website).await {
+      log::warn!("auth provider failed: {e}");
+    }
+
+    self.changes.start();
+    self.website.handle().await;
+    self.crawl_stats.flush().await;
+    self.changes.finish(&self.website);
+  }
+}
@@ -0,0 +1,306 @@
+use crate::auth::NAuth;
+use crate::automation::NAutomationStep;
+use crate::blocking::NResourceBlocking;
+use crate::changes::NChangeTracking;
+use crate::dedupe::NDedupe;
+use crate::device::NViewport;
+use crate::login::NLoginConfig;
+use crate::normalize::NUrlNormalization;
+use crate::pdf::NPdfOptions;
+use crate::proxy::NProxyRotation;
+use serde::{Deserialize, Serialize};
+use spider::reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// the value replacing the secrets in the reported config.
+pub const REDACTED: &str = "[redacted]";
+
+/// the headers carrying credentials redacted in the reported config.
+const CREDENTIAL_HEADERS: [&str; 5] = [
+  "authorization",
+  "proxy-authorization",
+  "cookie",
+  "set-cookie",
+  "x-api-key",
+];
+
+/// a duration to wait for.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NWaitFor {
+  /// The seconds to wait.
+  pub seconds: Option<u32>,
+  /// The nanoseconds to wait.
+  pub nanos: Option<u32>,
+}
+
+/// an element to wait for.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NWaitForSelector {
+  /// The CSS selector of the element.
+  pub selector: String,
+  /// The seconds to wait at most.
+  pub seconds: Option<u32>,
+  /// The nanoseconds to wait at most.
+  pub nanos: Option<u32>,
+}
+
+/// the chrome network interception.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NChromeIntercept {
+  /// Only allow the content that matches the host.
+  pub enabled: bool,
+  /// Block the images. Defaults to false.
+  pub block_images: Option<bool>,
+}
+
+/// the cron job to run.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NCron {
+  /// The cron expression.
+  pub expression: String,
+  /// The job to run. Defaults to crawl.
+  #[napi(ts_type = "'crawl' | 'scrape'")]
+  pub cron_type: Option<String>,
+}
+
+/// the website configuration with every builder option by the builder method name.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NWebsiteConfig {
+  /// The url to crawl.
+  pub url: String,
+  /// Do not convert the content to UTF-8.
+  pub raw_content: Option<bool>,
+  /// The HTTP headers.
+  pub headers: Option<HashMap<String, String>>,
+  /// The cookies as a Cookie header string or a list of cookies.
+  #[napi(ts_type = "string | Array<NCookie>")]
+  pub cookies: Option<serde_json::Value>,
+  /// The login step to run before the crawl.
+  pub login: Option<NLoginConfig>,
+  /// The credentials by origin.
+  pub auth: Option<HashMap<String, NAuth>>,
+  /// The user agent.
+  pub user_agent: Option<String>,
+  /// Respect the robots.txt file.
+  pub respect_robots_txt: Option<bool>,
+  /// Collect all the resources found on the pages.
+  pub full_resources: Option<bool>,
+  /// The chrome network interception.
+  pub chrome_intercept: Option<NChromeIntercept>,
+  /// The resources to block in headless mode.
+  pub resource_blocking: Option<NResourceBlocking>,
+  /// The url normalization.
+  pub url_normalization: Option<NUrlNormalization>,
+  /// The near-duplicate page detection.
+  pub dedupe: Option<NDedupe>,
+  /// The change detection between runs.
+  pub change_tracking: Option<NChangeTracking>,
+  /// The connection url for the chrome instance.
+  pub chrome_connection: Option<String>,
+  /// Preserve the HOST header.
+  pub preserve_host_header: Option<bool>,
+  /// Crawl the subdomains.
+  pub subdomains: Option<bool>,
+  /// Crawl the TLD pages.
+  pub tld: Option<bool>,
+  /// Only use HTTP/2.
+  pub http2_prior_knowledge: Option<bool>,
+  /// The request timeout per page in milliseconds.
+  pub request_timeout: Option<u32>,
+  /// The crawl timeout in milliseconds.
+  pub crawl_timeout: Option<u32>,
+  /// The amount of pages to crawl.
+  pub limit: Option<u32>,
+  /// The external domains to crawl.
+  pub external_domains: Option<Vec<String>>,
+  /// The device preset. The viewport and user agent options take priority.
+  pub device: Option<String>,
+  /// The browser viewport.
+  pub viewport: Option<NViewport>,
+  /// The browser timezone.
+  pub timezone: Option<String>,
+  /// The browser locale.
+  pub locale: Option<String>,
+  /// Use stealth mode for headless requests. Not reported when the default stealth mode of chrome is kept.
+  pub stealth: Option<bool>,
+  /// Accept invalid certificates.
+  pub danger_accept_invalid_certs: Option<bool>,
+  /// The crawl budget by path.
+  pub budget: Option<HashMap<String, u32>>,
+  /// The max redirects allowed for a request.
+  pub redirect_limit: Option<u32>,
+  /// The redirect policy.
+  #[napi(ts_type = "'strict' | 'loose'")]
+  pub redirect_policy: Option<String>,
+  /// The regex blacklist urls.
+  pub blacklist_url: Option<Vec<String>>,
+  /// The regex whitelist urls.
+  pub whitelist_url: Option<Vec<String>>,
+  /// Wait for a delay on the page in headless mode.
+  pub wait_for_delay: Option<NWaitFor>,
+  /// Wait for an element on the page in headless mode.
+  pub wait_for_selector: Option<NWaitForSelector>,
+  /// Wait for the network to be idle in headless mode.
+  pub wait_for_idle_network: Option<NWaitFor>,
+  /// The automation steps by url path.
+  pub automation_scripts: Option<HashMap<String, Vec<NAutomationStep>>>,
+  /// The javascript expressions to evaluate by url path.
+  pub evaluate_scripts: Option<HashMap<String, String>>,
+  /// The cron job.
+  pub cron: Option<NCron>,
+  /// The OpenAI configs.
+  pub openai: Option<serde_json::Value>,
+  /// The screenshot configs.
+  pub screenshot: Option<serde_json::Value>,
+  /// The PDF render options.
+  pub pdf: Option<NPdfOptions>,
+  /// The delay between requests in milliseconds.
+  pub delay: Option<u32>,
+  /// The crawl depth limit.
+  pub depth: Option<u32>,
+  /// The amount of retries for the failed requests.
+  pub retry: Option<u32>,
+  /// Return the links found on the pages.
+  pub return_page_links: Option<bool>,
+  /// Report the urls skipped by the crawl rules.
+  pub skip_report: Option<bool>,
+  /// Cache the pages following HTTP rules.
+  pub caching: Option<bool>,
+  /// The sitemap url.
+  pub sitemap: Option<String>,
+  /// The proxies.
+  pub proxies: Option<Vec<String>>,
+  /// The proxy rotation.
+  pub proxy_rotation: Option<NProxyRotation>,
+}
+
+impl NWebsiteConfig {
+  /// parse the config from a javascript object.
+  pub fn from_value(value: serde_json::Value) -> Result<NWebsiteConfig, String> {
+    serde_json::from_value(value).map_err(|e| format!("invalid website config: {e}"))
+  }
+
+  /// the config with the passwords, login form values, cookie values, OpenAI api keys, credential headers and proxy passwords redacted.
+  pub fn redacted(mut self) -> NWebsiteConfig {
+    for auth in self.auth.iter_mut().flat_map(|auth| auth.values_mut()) {
+      for credentials in auth.basic.iter_mut().chain(auth.digest.iter_mut()) {
+        credentials.password = REDACTED.into();
+      }
+    }
+
+    for login in self.login.iter_mut() {
+      for value in login.form.iter_mut().flat_map(|form| form.values_mut()) {
+        *value = REDACTED.into();
+      }
+    }
+
+    for (name, value) in self
+      .headers
+      .iter_mut()
+      .flat_map(|headers| headers.iter_mut())
+    {
+      if CREDENTIAL_HEADERS
+        .iter()
+        .any(|header| name.eq_ignore_ascii_case(header))
+      {
+        *value = REDACTED.into();
+      }
+    }
+
+    match self.cookies {
+      Some(serde_json::Value::String(ref mut cookies)) => {
+        *cookies = cookies
+          .split(';')
+          .filter_map(|cookie| cookie.split_once('='))
+          .map(|(name, _)| format!("{}={REDACTED}", name.trim()))
+          .collect::<Vec<_>>()
+          .join("; ");
+      }
+      Some(serde_json::Value::Array(ref mut cookies)) => {
+        for cookie in cookies
+          .iter_mut()
+          .filter_map(|cookie| cookie.as_object_mut())
+        {
+          if cookie.contains_key("value") {
+            cookie.insert("value".into(), REDACTED.into());
+          }
+        }
+      }
+      _ => (),
+    }
+
+    if let Some(ref mut openai) = self.openai {
+      redact_api_keys(openai);
+    }
+
+    for proxy in self
+      .proxies
+      .iter_mut()
+      .flat_map(|proxies| proxies.iter_mut())
+    {
+      if let Ok(mut url) = spider::url::Url::parse(proxy) {
+        if url.password().is_some() && url.set_password(Some(REDACTED)).is_ok() {
+          *proxy = url.into();
+        }
+      }
+    }
+
+    self
+  }
+
+  /// parse the config from a JSON or YAML file.
+  pub fn from_file(path: &str) -> Result<NWebsiteConfig, String> {
+    let content =
+      std::fs::read_to_string(path).map_err(|e| format!("failed to read {path}: {e}"))?;
+    let is_yaml = std::path::Path::new(path)
+      .extension()
+      .is_some_and(|ext| ext == "yaml" || ext == "yml");
+
+    if is_yaml {
+      serde_yaml::from_str(&content).map_err(|e| format!("invalid website config {path}: {e}"))
+    } else {
+      serde_json::from_str(&content).map_err(|e| format!("invalid website config {path}: {e}"))
+    }
+  }
+}
+
+/// redact the api keys of the OpenAI configs including the configs of the prompt url map.
+fn redact_api_keys(value: &mut serde_json::Value) {
+  match value {
+    serde_json::Value::Object(map) => {
+      for (key, value) in map.iter_mut() {
+        if (key == "api_key" || key == "apiKey") && value.is_string() {
+          *value = REDACTED.into();
+        } else {
+          redact_api_keys(value);
+        }
+      }
+    }
+    serde_json::Value::Array(values) => values.iter_mut().for_each(redact_api_keys),
+    _ => (),
+  }
+}
+
+/// convert the config headers to a header map.
+pub fn to_header_map(headers: HashMap<String, String>) -> Result<HeaderMap, String> {
+  let mut header_map = HeaderMap::new();
+
+  for (key, value) in headers {
+    let name = HeaderName::from_str(&key).map_err(|e| format!("invalid header {key}: {e}"))?;
+    let value = HeaderValue::from_str(&value).map_err(|e| format!("invalid header {key}: {e}"))?;
+    header_map.append(name, value);
+  }
+
+  Ok(header_map)
+}
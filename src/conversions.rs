@@ -35,28 +35,28 @@ pub fn object_to_u8(obj: ObjectConvert) -> Result<Vec<u8>, napi::Error> {
         let mut block = false;
 
         // todo: method to go through all napi values to get types instead of long chain map
-        match obj.get::<&str, String>(&key) {
+        match obj.get::<&str, String>(key) {
           Ok(s) => {
             fp();
             ss.push(b'"');
             ss.extend(s.unwrap_or_default().as_bytes());
             ss.push(b'"');
           }
-          _ => match obj.get::<&str, u32>(&key) {
+          _ => match obj.get::<&str, u32>(key) {
             Ok(s) => {
               fp();
               ss.push(b'"');
               ss.extend(s.unwrap_or_default().to_string().as_bytes());
               ss.push(b'"');
             }
-            _ => match obj.get::<&str, i32>(&key) {
+            _ => match obj.get::<&str, i32>(key) {
               Ok(s) => {
                 fp();
                 ss.push(b'"');
                 ss.extend(s.unwrap_or_default().to_string().as_bytes());
                 ss.push(b'"');
               }
-              _ => match obj.get::<&str, Buffer>(&key) {
+              _ => match obj.get::<&str, Buffer>(key) {
                 Ok(s) => {
                   fp();
                   let d = serde_json::to_string(
@@ -64,17 +64,16 @@ pub fn object_to_u8(obj: ObjectConvert) -> Result<Vec<u8>, napi::Error> {
                   )?;
                   ss.extend(d.as_bytes());
                 }
-                _ => match obj.get::<&str, Null>(&key) {
+                _ => match obj.get::<&str, Null>(key) {
                   Ok(_) => {
                     fp();
                     ss.extend(b"null");
                   }
-                  _ => match obj.get::<&str, Undefined>(&key) {
-                    Ok(_) => {
+                  _ => {
+                    if obj.get::<&str, Undefined>(key).is_ok() {
                       block = true;
                     }
-                    _ => (),
-                  },
+                  }
                 },
               },
             },
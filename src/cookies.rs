@@ -0,0 +1,235 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// a cookie used for the crawl.
+#[napi(object)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NCookie {
+  /// The name of the cookie.
+  pub name: String,
+  /// The value of the cookie.
+  pub value: String,
+  /// The domain of the cookie. A leading dot includes the subdomains.
+  pub domain: String,
+  /// The path of the cookie. Defaults to /.
+  pub path: Option<String>,
+  /// The expiration as unix time in seconds. Session cookies do not expire.
+  pub expires: Option<f64>,
+  /// Only send the cookie over HTTPS.
+  pub secure: Option<bool>,
+  /// The cookie is not accessible from javascript.
+  pub http_only: Option<bool>,
+}
+
+impl NCookie {
+  /// the path of the cookie.
+  pub fn get_path(&self) -> &str {
+    match self.path.as_deref() {
+      Some(path) if !path.is_empty() => path,
+      _ => "/",
+    }
+  }
+
+  /// the url to use when adding the cookie to the jar.
+  pub fn url(&self) -> Option<spider::url::Url> {
+    let scheme = if self.secure.unwrap_or_default() {
+      "https"
+    } else {
+      "http"
+    };
+    spider::url::Url::parse(&format!(
+      "{}://{}{}",
+      scheme,
+      self.domain.trim_start_matches('.'),
+      self.get_path()
+    ))
+    .ok()
+  }
+
+  /// the Set-Cookie header value of the cookie.
+  pub fn to_set_cookie(&self) -> String {
+    let mut set_cookie = format!("{}={}; Path={}", self.name, self.value, self.get_path());
+
+    if self.domain.starts_with('.') {
+      set_cookie.push_str("; Domain=");
+      set_cookie.push_str(&self.domain);
+    }
+    if let Some(expires) = self.expires {
+      let max_age = expires as i64 - unix_now();
+      set_cookie.push_str(&format!("; Max-Age={}", max_age.max(0)));
+    }
+    if self.secure.unwrap_or_default() {
+      set_cookie.push_str("; Secure");
+    }
+    if self.http_only.unwrap_or_default() {
+      set_cookie.push_str("; HttpOnly");
+    }
+
+    set_cookie
+  }
+
+  /// parse a Set-Cookie header value from a response of the url.
+  pub fn from_set_cookie(set_cookie: &str, url: &spider::url::Url) -> Option<NCookie> {
+    let cookie = cookie::Cookie::parse(set_cookie).ok()?;
+
+    Some(NCookie {
+      name: cookie.name().into(),
+      value: cookie.value().into(),
+      domain: match cookie.domain() {
+        Some(domain) => format!(".{}", domain.trim_start_matches('.')),
+        _ => url.host_str().unwrap_or_default().into(),
+      },
+      path: Some(cookie.path().unwrap_or("/").into()),
+      expires: match cookie.max_age() {
+        Some(max_age) => Some((unix_now() + max_age.whole_seconds()) as f64),
+        _ => cookie
+          .expires_datetime()
+          .map(|expires| expires.unix_timestamp() as f64),
+      },
+      secure: cookie.secure(),
+      http_only: cookie.http_only(),
+    })
+  }
+
+  /// is the cookie expired.
+  pub fn is_expired(&self) -> bool {
+    match self.expires {
+      Some(expires) => expires > 0.0 && (expires as i64) < unix_now(),
+      _ => false,
+    }
+  }
+}
+
+/// the current unix time in seconds.
+fn unix_now() -> i64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs() as i64)
+    .unwrap_or_default()
+}
+
+/// parse cookies from the Netscape cookies.txt format.
+pub fn parse_netscape(data: &str) -> Vec<NCookie> {
+  let mut cookies = Vec::new();
+
+  for line in data.lines() {
+    let line = line.trim();
+    let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+      Some(line) => (line, true),
+      _ => (line, false),
+    };
+
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let fields = line.split('\t').collect::<Vec<&str>>();
+
+    if fields.len() < 7 {
+      continue;
+    }
+
+    let expires = fields[4].parse::<f64>().unwrap_or_default();
+
+    cookies.push(NCookie {
+      domain: fields[0].into(),
+      path: Some(fields[2].into()),
+      secure: Some(fields[3].eq_ignore_ascii_case("TRUE")),
+      expires: if expires > 0.0 { Some(expires) } else { None },
+      name: fields[5].into(),
+      value: fields[6..].join("\t"),
+      http_only: Some(http_only),
+    });
+  }
+
+  cookies
+}
+
+/// write the cookies in the Netscape cookies.txt format.
+pub fn to_netscape(cookies: &[NCookie]) -> String {
+  let mut data = String::from("# Netscape HTTP Cookie File\n");
+
+  for cookie in cookies {
+    let bool_str = |b: bool| if b { "TRUE" } else { "FALSE" };
+
+    data.push_str(&format!(
+      "{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+      if cookie.http_only.unwrap_or_default() {
+        "#HttpOnly_"
+      } else {
+        ""
+      },
+      cookie.domain,
+      bool_str(cookie.domain.starts_with('.')),
+      cookie.get_path(),
+      bool_str(cookie.secure.unwrap_or_default()),
+      cookie.expires.unwrap_or_default() as i64,
+      cookie.name,
+      cookie.value
+    ));
+  }
+
+  data
+}
+
+/// the cookies imported and received during the crawl.
+#[derive(Default)]
+pub struct CookieStore {
+  /// the cookies by domain, path and name.
+  cookies: Mutex<IndexMap<(String, String, String), NCookie>>,
+}
+
+impl CookieStore {
+  /// insert or replace a cookie.
+  pub fn insert(&self, cookie: NCookie) {
+    if let Ok(mut cookies) = self.cookies.lock() {
+      let key = (
+        cookie.domain.clone(),
+        cookie.get_path().to_string(),
+        cookie.name.clone(),
+      );
+      if cookie.is_expired() {
+        cookies.shift_remove(&key);
+      } else {
+        cookies.insert(key, cookie);
+      }
+    }
+  }
+
+  /// record the cookies set by the page response.
+  pub fn record(&self, page: &spider::page::Page) {
+    if let Some(ref headers) = page.headers {
+      if let Ok(url) = spider::url::Url::parse(page.get_url()) {
+        for set_cookie in headers.get_all(spider::reqwest::header::SET_COOKIE) {
+          if let Ok(set_cookie) = set_cookie.to_str() {
+            if let Some(cookie) = NCookie::from_set_cookie(set_cookie, &url) {
+              self.insert(cookie);
+            }
+          }
+        }
+      }
+    }
+  }
+
+  /// get all of the cookies that are not expired.
+  pub fn get_all(&self) -> Vec<NCookie> {
+    match self.cookies.lock() {
+      Ok(cookies) => cookies
+        .values()
+        .filter(|cookie| !cookie.is_expired())
+        .cloned()
+        .collect(),
+      _ => Default::default(),
+    }
+  }
+
+  /// remove all of the cookies.
+  pub fn clear(&self) {
+    if let Ok(mut cookies) = self.cookies.lock() {
+      cookies.clear();
+    }
+  }
+}
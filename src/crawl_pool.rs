@@ -0,0 +1,237 @@
+use crate::{NCrawlStats, NPage, Website};
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use spider::reqwest::{Request, Response};
+use spider::reqwest_middleware::{Middleware, Next};
+use spider::tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// the crawl pool options.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct NCrawlPoolOptions {
+  /// The amount of requests running at the same time across all sites. Defaults to 100. The headless crawls are not counted.
+  pub concurrency: Option<u32>,
+  /// The amount of requests running at the same time per domain. Defaults to 10.
+  pub per_domain: Option<u32>,
+  /// The amount of sites crawling at the same time. Defaults to 0 to crawl all sites at once.
+  pub max_sites: Option<u32>,
+}
+
+/// the result of a site crawled by the pool.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct NSiteResult {
+  /// The url of the site.
+  pub url: String,
+  /// The crawl status.
+  pub status: String,
+  /// The crawl statistics of the site.
+  pub stats: NCrawlStats,
+  /// The error when the crawl failed to start or stopped unexpectedly.
+  pub error: Option<String>,
+}
+
+/// the request limits shared by the clients of the sites.
+#[derive(Default)]
+pub struct RequestLimits {
+  /// the requests allowed at the same time across all sites.
+  global: Option<Arc<Semaphore>>,
+  /// the requests allowed at the same time per domain.
+  per_domain: usize,
+  /// the requests allowed by domain.
+  domains: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl RequestLimits {
+  /// create the limits. A value of 0 disables the limit.
+  pub fn new(concurrency: u32, per_domain: u32) -> RequestLimits {
+    RequestLimits {
+      global: if concurrency > 0 {
+        Some(Arc::new(Semaphore::new(concurrency as usize)))
+      } else {
+        None
+      },
+      per_domain: per_domain as usize,
+      domains: Default::default(),
+    }
+  }
+
+  /// is any limit set.
+  pub fn is_enabled(&self) -> bool {
+    self.global.is_some() || self.per_domain > 0
+  }
+
+  /// wait for the domain and the global slots. The slots are held until the permits drop.
+  async fn acquire(&self, host: &str) -> Vec<OwnedSemaphorePermit> {
+    let mut permits = Vec::with_capacity(2);
+
+    let domain = if self.per_domain > 0 {
+      match self.domains.lock() {
+        Ok(mut domains) => Some(
+          domains
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.per_domain)))
+            .clone(),
+        ),
+        _ => None,
+      }
+    } else {
+      None
+    };
+
+    // the domain slot first so busy domains do not hold the global slots.
+    for semaphore in domain.iter().chain(self.global.iter()) {
+      if let Ok(permit) = semaphore.clone().acquire_owned().await {
+        permits.push(permit);
+      }
+    }
+
+    permits
+  }
+}
+
+/// the client middleware holding the request slots for the duration of the request.
+pub struct LimitMiddleware(pub Arc<RequestLimits>);
+
+#[async_trait::async_trait]
+impl Middleware for LimitMiddleware {
+  async fn handle(
+    &self,
+    req: Request,
+    extensions: &mut http::Extensions,
+    next: Next<'_>,
+  ) -> spider::reqwest_middleware::Result<Response> {
+    if !self.0.is_enabled() {
+      return next.run(req, extensions).await;
+    }
+
+    let host = req.url().host_str().unwrap_or_default().to_string();
+    let permits = self.0.acquire(&host).await;
+    let res = next.run(req, extensions).await;
+
+    drop(permits);
+    res
+  }
+}
+
+/// crawl many sites sharing the request limits and the page subscription. The limits are held by the HTTP client so the headless crawls, fetching the pages in chrome, are only limited by the crawl concurrency of each site capped to the domain limit and the global limit does not apply to them.
+#[napi]
+pub struct CrawlPool {
+  /// the pool options.
+  options: NCrawlPoolOptions,
+  /// the request limits shared by the sites.
+  limits: Arc<RequestLimits>,
+  /// the sites to crawl.
+  sites: Vec<Website>,
+}
+
+#[napi]
+impl CrawlPool {
+  #[napi(constructor)]
+  /// create a new crawl pool.
+  pub fn new(options: Option<NCrawlPoolOptions>) -> Self {
+    let options = options.unwrap_or_default();
+
+    CrawlPool {
+      limits: Arc::new(RequestLimits::new(
+        options.concurrency.unwrap_or(100),
+        options.per_domain.unwrap_or(10),
+      )),
+      options,
+      sites: Vec::new(),
+    }
+  }
+
+  #[napi]
+  /// add a site to crawl by url.
+  pub fn add(&mut self, url: String) -> &Self {
+    self.sites.push(Website::new(url, None));
+    self
+  }
+
+  #[napi]
+  /// add a copy of the website to crawl with its configuration and callbacks. The website passed in is not crawled, use the pool results for the pages and status.
+  pub fn add_website(&mut self, website: &Website) -> napi::Result<&Self> {
+    self.sites.push(website.clone_website()?);
+    Ok(self)
+  }
+
+  #[napi(getter)]
+  /// the amount of sites in the pool.
+  pub fn size(&self) -> u32 {
+    self.sites.len() as u32
+  }
+
+  #[napi]
+  /// crawl all of the sites sending the pages of every site to one callback. The site callback runs when a site finishes.
+  pub async unsafe fn crawl(
+    &mut self,
+    on_page_event: Option<ThreadsafeFunction<NPage>>,
+    on_site_complete: Option<ThreadsafeFunction<NSiteResult>>,
+    headless: Option<bool>,
+  ) -> napi::Result<Vec<NSiteResult>> {
+    let headless = headless.unwrap_or_default();
+    let max_sites = match self.options.max_sites.unwrap_or_default() {
+      0 => self.sites.len().max(1),
+      max_sites => max_sites as usize,
+    };
+    let site_permits = Arc::new(Semaphore::new(max_sites));
+    let per_domain = self.options.per_domain.unwrap_or(10);
+    let mut handles = Vec::with_capacity(self.sites.len());
+    let mut urls = Vec::with_capacity(self.sites.len());
+
+    for mut website in std::mem::take(&mut self.sites) {
+      let url = website.site_result(None).url;
+      let on_page_event = on_page_event.clone();
+      let on_site_complete = on_site_complete.clone();
+      let site_permits = site_permits.clone();
+
+      website.with_request_limits(self.limits.clone(), per_domain);
+
+      handles.push(spider::tokio::spawn(async move {
+        let _permit = site_permits.acquire_owned().await;
+        // the pool owns the website so no javascript reference can use it during the crawl.
+        let crawl = unsafe { website.crawl(on_page_event, None, Some(headless)) };
+        let error = crawl.await.err().map(|e| e.reason);
+        let result = website.site_result(error);
+
+        if let Some(callback) = on_site_complete {
+          callback.call(Ok(result.clone()), ThreadsafeFunctionCallMode::NonBlocking);
+        }
+
+        (website, result)
+      }));
+      urls.push(url);
+    }
+
+    let mut results = Vec::with_capacity(handles.len());
+
+    for (handle, url) in handles.into_iter().zip(urls) {
+      match handle.await {
+        Ok((website, result)) => {
+          self.sites.push(website);
+          results.push(result);
+        }
+        Err(e) => {
+          log::warn!("crawl pool site failed: {e}");
+          // the site is dropped from the pool since the task owned it.
+          let result = NSiteResult {
+            url,
+            status: spider::website::CrawlStatus::default().to_string(),
+            stats: Default::default(),
+            error: Some(format!("the site crawl failed: {e}")),
+          };
+
+          if let Some(ref callback) = on_site_complete {
+            callback.call(Ok(result.clone()), ThreadsafeFunctionCallMode::NonBlocking);
+          }
+
+          results.push(result);
+        }
+      }
+    }
+
+    Ok(results)
+  }
+}
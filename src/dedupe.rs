@@ -0,0 +1,238 @@
+use crate::options::Options;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// the amount of words hashed together for the fingerprint.
+const SHINGLE_SIZE: usize = 3;
+/// the max amount of bits different between near-duplicate pages by default.
+const DEFAULT_THRESHOLD: u32 = 3;
+/// the amount of 16 bit bands of the fingerprint indexed.
+const BANDS: usize = 4;
+
+/// the near-duplicate page detection options.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NDedupe {
+  /// The max amount of bits different between the fingerprints of near-duplicate pages from 0 to 64. Defaults to 3.
+  pub threshold: Option<u32>,
+}
+
+/// a near-duplicate page that was not sent.
+#[napi(object)]
+#[derive(Default, Clone, Debug)]
+pub struct NDuplicatePage {
+  /// The url of the page.
+  pub url: String,
+  /// The url of the page sent with similar content.
+  pub duplicate_of: String,
+  /// The amount of bits different between the fingerprints.
+  pub distance: u32,
+}
+
+/// the fingerprint of a page and the page it duplicates.
+#[derive(Clone, Debug)]
+pub struct DedupeResult {
+  /// the SimHash of the page text.
+  pub fingerprint: u64,
+  /// the near-duplicate found.
+  pub duplicate: Option<NDuplicatePage>,
+}
+
+/// the pages checked and the pages sent indexed by the bands of the fingerprint.
+#[derive(Default)]
+struct Fingerprints {
+  /// the result by page url kept for every listener of the page.
+  pages: IndexMap<String, DedupeResult>,
+  /// the index of the pages sent by the value of each band.
+  bands: [HashMap<u16, Vec<usize>>; BANDS],
+}
+
+impl Fingerprints {
+  /// the url and fingerprint of the pages sent that can be within the threshold in the order checked.
+  fn candidates(&self, fingerprint: u64, threshold: u32) -> Vec<(String, u64)> {
+    let mut indexes: Vec<usize> = if (threshold as usize) < BANDS {
+      // fewer bits different than bands leaves at least one band equal.
+      self
+        .bands
+        .iter()
+        .enumerate()
+        .filter_map(|(band, pages)| pages.get(&band_value(fingerprint, band)))
+        .flatten()
+        .copied()
+        .collect()
+    } else {
+      self.bands[0].values().flatten().copied().collect()
+    };
+
+    indexes.sort_unstable();
+    indexes.dedup();
+
+    indexes
+      .into_iter()
+      .filter_map(|index| self.pages.get_index(index))
+      .map(|(url, page)| (url.clone(), page.fingerprint))
+      .collect()
+  }
+
+  /// keep the result and index the page when sent.
+  fn insert(&mut self, url: &str, result: DedupeResult) {
+    let fingerprint = result.fingerprint;
+    // pages without text are never duplicates.
+    let sent = fingerprint != 0 && result.duplicate.is_none();
+    let (index, _) = self.pages.insert_full(url.to_string(), result);
+
+    if sent {
+      for (band, pages) in self.bands.iter_mut().enumerate() {
+        pages
+          .entry(band_value(fingerprint, band))
+          .or_default()
+          .push(index);
+      }
+    }
+  }
+}
+
+/// the value of the 16 bit band of the fingerprint.
+fn band_value(fingerprint: u64, band: usize) -> u16 {
+  (fingerprint >> (band * 16)) as u16
+}
+
+/// detect the near-duplicate pages using the SimHash of the page text.
+#[derive(Default)]
+pub struct Dedupe {
+  /// the dedupe options.
+  pub options: Options<NDedupe>,
+  /// the fingerprints of the pages checked.
+  fingerprints: Mutex<Fingerprints>,
+}
+
+impl Dedupe {
+  /// clear the pages of the last run.
+  pub fn clear(&self) {
+    if let Ok(mut fingerprints) = self.fingerprints.lock() {
+      *fingerprints = Default::default();
+    }
+  }
+
+  /// the fingerprint of the page and the earlier page with similar content.
+  pub fn check(&self, url: &str, html: &str) -> Option<DedupeResult> {
+    let threshold = self
+      .options
+      .get()?
+      .threshold
+      .unwrap_or(DEFAULT_THRESHOLD)
+      .min(64);
+
+    if let Some(result) = self.fingerprints.lock().ok()?.pages.get(url) {
+      return Some(result.clone());
+    }
+
+    let fingerprint = simhash(html);
+    let candidates = if fingerprint == 0 {
+      Default::default()
+    } else {
+      self
+        .fingerprints
+        .lock()
+        .ok()?
+        .candidates(fingerprint, threshold)
+    };
+
+    let duplicate = candidates
+      .into_iter()
+      .map(|(page_url, page)| (page_url, (page ^ fingerprint).count_ones()))
+      .filter(|(_, distance)| *distance <= threshold)
+      .min_by_key(|(_, distance)| *distance)
+      .map(|(page_url, distance)| NDuplicatePage {
+        url: url.to_string(),
+        duplicate_of: page_url,
+        distance,
+      });
+
+    let result = DedupeResult {
+      fingerprint,
+      duplicate,
+    };
+    let mut fingerprints = self.fingerprints.lock().ok()?;
+
+    // another listener checked the page while hashing.
+    if let Some(result) = fingerprints.pages.get(url) {
+      return Some(result.clone());
+    }
+
+    fingerprints.insert(url, result.clone());
+
+    Some(result)
+  }
+
+  /// the near-duplicate pages found during the last crawl.
+  pub fn duplicates(&self) -> Vec<NDuplicatePage> {
+    match self.fingerprints.lock() {
+      Ok(fingerprints) => fingerprints
+        .pages
+        .values()
+        .filter_map(|page| page.duplicate.clone())
+        .collect(),
+      _ => Default::default(),
+    }
+  }
+}
+
+/// the SimHash of the visible text of the html using word shingles.
+pub fn simhash(html: &str) -> u64 {
+  let fragment = scraper::Html::parse_document(html);
+  let mut words: Vec<String> = Vec::new();
+
+  for node in fragment.root_element().descendants() {
+    let text = match node.value().as_text() {
+      Some(text) => text,
+      _ => continue,
+    };
+    let hidden = node
+      .parent()
+      .and_then(|parent| parent.value().as_element())
+      .is_some_and(|element| {
+        matches!(element.name(), "script" | "style" | "noscript" | "template")
+      });
+
+    if !hidden {
+      words.extend(
+        text
+          .split(|c: char| !c.is_alphanumeric())
+          .filter(|word| !word.is_empty())
+          .map(|word| word.to_lowercase()),
+      );
+    }
+  }
+
+  let mut weights = [0i64; 64];
+  let shingles = words.windows(SHINGLE_SIZE.min(words.len().max(1)));
+
+  for shingle in shingles {
+    let hash = fnv1a(shingle.join(" ").as_bytes());
+
+    for (bit, weight) in weights.iter_mut().enumerate() {
+      if hash & (1 << bit) != 0 {
+        *weight += 1;
+      } else {
+        *weight -= 1;
+      }
+    }
+  }
+
+  weights
+    .iter()
+    .enumerate()
+    .filter(|(_, weight)| **weight > 0)
+    .fold(0, |fingerprint, (bit, _)| fingerprint | (1 << bit))
+}
+
+/// the 64 bit FNV-1a hash stable across runs.
+fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+    (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+  })
+}
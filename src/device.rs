@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+/// the browser viewport.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NViewport {
+  /// The screen width in pixels.
+  pub width: u32,
+  /// The screen height in pixels.
+  pub height: u32,
+  /// The device scale factor. Defaults to 1.
+  pub device_scale_factor: Option<f64>,
+  /// Emulate a mobile device. Defaults to false.
+  pub mobile: Option<bool>,
+  /// Use the landscape orientation. Defaults to false.
+  pub landscape: Option<bool>,
+  /// Emulate a touch screen. Defaults to the mobile value.
+  pub has_touch: Option<bool>,
+}
+
+impl From<NViewport> for spider::configuration::Viewport {
+  fn from(viewport: NViewport) -> Self {
+    let mobile = viewport.mobile.unwrap_or_default();
+
+    spider::configuration::Viewport {
+      width: viewport.width,
+      height: viewport.height,
+      device_scale_factor: viewport.device_scale_factor,
+      emulating_mobile: mobile,
+      is_landscape: viewport.landscape.unwrap_or_default(),
+      has_touch: viewport.has_touch.unwrap_or(mobile),
+    }
+  }
+}
+
+impl From<spider::configuration::Viewport> for NViewport {
+  fn from(viewport: spider::configuration::Viewport) -> Self {
+    NViewport {
+      width: viewport.width,
+      height: viewport.height,
+      device_scale_factor: viewport.device_scale_factor,
+      mobile: Some(viewport.emulating_mobile),
+      landscape: Some(viewport.is_landscape),
+      has_touch: Some(viewport.has_touch),
+    }
+  }
+}
+
+/// the device presets by name with the viewport and user agent.
+const DEVICES: &[(&str, u32, u32, f64, bool, &str)] = &[
+  (
+    "desktop",
+    1920,
+    1080,
+    1.0,
+    false,
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+  ),
+  (
+    "laptop",
+    1366,
+    768,
+    1.0,
+    false,
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+  ),
+  (
+    "iphone-15",
+    393,
+    852,
+    3.0,
+    true,
+    "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+  ),
+  (
+    "iphone-se",
+    375,
+    667,
+    2.0,
+    true,
+    "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+  ),
+  (
+    "ipad",
+    820,
+    1180,
+    2.0,
+    true,
+    "Mozilla/5.0 (iPad; CPU OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+  ),
+  (
+    "pixel-7",
+    412,
+    915,
+    2.625,
+    true,
+    "Mozilla/5.0 (Linux; Android 14; Pixel 7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36",
+  ),
+  (
+    "galaxy-s23",
+    360,
+    780,
+    3.0,
+    true,
+    "Mozilla/5.0 (Linux; Android 14; SM-S911B) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36",
+  ),
+];
+
+/// get the viewport and user agent of the device preset.
+pub fn device_preset(name: &str) -> Option<(NViewport, &'static str)> {
+  DEVICES
+    .iter()
+    .find(|device| device.0.eq_ignore_ascii_case(name))
+    .map(|(_, width, height, scale, mobile, user_agent)| {
+      (
+        NViewport {
+          width: *width,
+          height: *height,
+          device_scale_factor: Some(*scale),
+          mobile: Some(*mobile),
+          landscape: Some(false),
+          has_touch: Some(*mobile),
+        },
+        *user_agent,
+      )
+    })
+}
+
+/// the names of the device presets.
+pub fn device_names() -> Vec<&'static str> {
+  DEVICES.iter().map(|device| device.0).collect()
+}
@@ -0,0 +1,92 @@
+/// a page that failed during the crawl.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct NCrawlError {
+  /// The url that failed.
+  pub url: String,
+  /// The stage the request failed at: dns, connect, tls, timeout, http, parse or robots.
+  #[napi(ts_type = "'dns' | 'connect' | 'tls' | 'timeout' | 'http' | 'parse' | 'robots'")]
+  pub stage: String,
+  /// The error message.
+  pub message: String,
+  /// The HTTP status code.
+  pub status_code: u16,
+  /// The amount of attempts made for the url.
+  pub attempts: u32,
+}
+
+impl NCrawlError {
+  /// establish a new error from the page if the request failed. The page is retried up to the retry count while it needs a retry.
+  pub fn new(res: &spider::page::Page, retry: u8) -> Option<NCrawlError> {
+    let status_code = res.status_code;
+
+    if res.error_status.is_none() && (status_code.is_success() || status_code.is_redirection()) {
+      return None;
+    }
+
+    let (stage, message) = match res.error_status {
+      Some(ref error) => (error_stage(error, status_code.as_u16()), error.to_string()),
+      _ => (
+        "http",
+        status_code
+          .canonical_reason()
+          .unwrap_or("Unknown status code")
+          .to_string(),
+      ),
+    };
+
+    Some(NCrawlError {
+      url: res.get_url().into(),
+      stage: stage.into(),
+      message,
+      status_code: status_code.as_u16(),
+      attempts: if res.needs_retry() {
+        1 + retry as u32
+      } else {
+        1
+      },
+    })
+  }
+
+  /// establish a new error for a url blocked by robots.txt that was never requested.
+  pub fn robots(url: String) -> NCrawlError {
+    NCrawlError {
+      url,
+      stage: "robots".into(),
+      message: "Blocked by robots.txt".into(),
+      status_code: 0,
+      attempts: 0,
+    }
+  }
+}
+
+/// determine the stage the request failed at from the error message and status code.
+fn error_stage(error: &str, status_code: u16) -> &'static str {
+  let error = error.to_lowercase();
+
+  if error.contains("dns")
+    || error.contains("lookup address")
+    || error.contains("name or service not known")
+    || status_code == 525
+  {
+    "dns"
+  } else if error.contains("tls")
+    || error.contains("ssl")
+    || error.contains("certificate")
+    || error.contains("handshake")
+    || status_code == 526
+  {
+    "tls"
+  } else if error.contains("timed out")
+    || error.contains("timeout")
+    || matches!(status_code, 408 | 504 | 524 | 598)
+  {
+    "timeout"
+  } else if error.contains("decod") || error.contains("parse") || error.contains("body") {
+    "parse"
+  } else if error.contains("connect") || error.contains("sending request") {
+    "connect"
+  } else {
+    "http"
+  }
+}
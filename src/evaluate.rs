@@ -0,0 +1,63 @@
+/// the opening tag of the element storing the evaluated result in the page.
+const EVALUATED_START: &str = "<script type=\"application/json\" id=\"spider-evaluated\">";
+/// the closing tag of the element storing the evaluated result in the page.
+const EVALUATED_END: &str = "</script>";
+
+/// wrap the expression to store its JSON result in the page before the content is captured.
+pub fn evaluate_script(expression: &str) -> String {
+  format!(
+    r#"(() => {{
+  const store = (value) => {{
+    const el = document.createElement('script');
+    el.type = 'application/json';
+    el.id = 'spider-evaluated';
+    el.textContent = JSON.stringify(value === undefined ? null : value).replace(/</g, '\\u003c');
+    document.documentElement.appendChild(el);
+  }};
+  const value = ({});
+  return value && typeof value.then === 'function' ? value.then(store) : store(value);
+}})()"#,
+    expression
+  )
+}
+
+/// convert the expressions by url pattern to the chrome execution scripts.
+pub fn to_execution_scripts(
+  scripts: std::collections::HashMap<String, String>,
+) -> spider::features::chrome_common::ExecutionScriptsMap {
+  scripts
+    .into_iter()
+    .map(|(pattern, expression)| (pattern, evaluate_script(&expression)))
+    .collect()
+}
+
+/// remove the evaluated result from the html returning the JSON value.
+pub fn take_evaluated(html: String) -> (String, Option<serde_json::Value>) {
+  let start = match html.find(EVALUATED_START) {
+    Some(start) => start,
+    _ => return (html, None),
+  };
+
+  let value_start = start + EVALUATED_START.len();
+
+  match html[value_start..].find(EVALUATED_END) {
+    Some(len) => {
+      let value = serde_json::from_str(&html[value_start..value_start + len]).ok();
+      let mut content = html[..start].to_string();
+      content.push_str(&html[value_start + len + EVALUATED_END.len()..]);
+      (content, value)
+    }
+    _ => (html, None),
+  }
+}
+
+/// get the evaluated result from the raw bytes.
+pub fn find_evaluated(bytes: &[u8]) -> Option<serde_json::Value> {
+  let marker = EVALUATED_START.as_bytes();
+
+  if bytes.windows(marker.len()).any(|w| w == marker) {
+    take_evaluated(String::from_utf8_lossy(bytes).into_owned()).1
+  } else {
+    None
+  }
+}
@@ -0,0 +1,150 @@
+use crate::blocking::ResourceBlocking;
+use crate::changes::ChangeStore;
+use crate::dedupe::Dedupe;
+use crate::normalize::UrlNormalizer;
+use crate::pdf::PdfRenderer;
+use crate::NPage;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// the steps run on every page before it is sent to javascript.
+#[derive(Default)]
+pub struct PageHooks {
+  /// render the pages as PDF.
+  pub pdf: Arc<PdfRenderer>,
+  /// the resource blocking rules.
+  pub blocking: ResourceBlocking,
+  /// the url normalization.
+  pub normalizer: Arc<UrlNormalizer>,
+  /// the near-duplicate page detection.
+  pub dedupe: Dedupe,
+  /// the content hashes compared between runs.
+  pub changes: Arc<ChangeStore>,
+  /// return the links found on the pages. The links are also collected for the skip report and the page limit.
+  return_page_links: AtomicBool,
+  /// look up the result of the evaluated scripts on the pages.
+  evaluate: AtomicBool,
+}
+
+impl PageHooks {
+  /// return the links found on the pages.
+  pub fn set_return_page_links(&self, return_page_links: bool) {
+    self
+      .return_page_links
+      .store(return_page_links, Ordering::Relaxed);
+  }
+
+  /// are the links found returned on the pages.
+  pub fn return_page_links(&self) -> bool {
+    self.return_page_links.load(Ordering::Relaxed)
+  }
+
+  /// look up the result of the evaluated scripts on the pages.
+  pub fn set_evaluate(&self, evaluate: bool) {
+    self.evaluate.store(evaluate, Ordering::Relaxed);
+  }
+
+  /// the page for javascript without the links collected only for the crawl.
+  pub fn new_page(&self, res: &spider::page::Page, raw_content: bool) -> NPage {
+    let mut page = NPage::new(res, raw_content, self.evaluate.load(Ordering::Relaxed));
+
+    if !self.return_page_links() {
+      page.links = None;
+    }
+
+    page
+  }
+
+  /// build the page for javascript.
+  pub fn page(&self, res: &spider::page::Page, raw_content: bool) -> NPage {
+    self.build(res, raw_content).0
+  }
+
+  /// is the page a near-duplicate of a page already sent.
+  pub fn is_duplicate(&self, res: &spider::page::Page) -> bool {
+    self.dedupe.options.is_enabled()
+      && self
+        .dedupe
+        .check(
+          res.get_url(),
+          &String::from_utf8_lossy(res.get_html_bytes_u8()),
+        )
+        .is_some_and(|result| result.duplicate.is_some())
+  }
+
+  /// build the page for javascript and check if the page is a near-duplicate.
+  fn build(&self, res: &spider::page::Page, raw_content: bool) -> (NPage, bool) {
+    let mut page = self.new_page(res, raw_content);
+    let html = if raw_content {
+      String::from_utf8_lossy(res.get_html_bytes_u8())
+    } else {
+      std::borrow::Cow::Borrowed(page.content.as_str())
+    };
+
+    let blocked_resources = if self.blocking.options.is_enabled() {
+      self.blocking.count_blocked(&html, &page.url)
+    } else {
+      None
+    };
+
+    let (original_url, normalized_url) = if self.normalizer.options.is_enabled() {
+      (
+        Some(self.normalizer.original(&page.url)),
+        Some(self.normalizer.page_url(&page.url, &html)),
+      )
+    } else {
+      (None, None)
+    };
+
+    let dedupe = self.dedupe.check(&page.url, &html);
+
+    page.blocked_resources = blocked_resources;
+    page.original_url = original_url;
+    page.normalized_url = normalized_url;
+    page.change_status = self
+      .changes
+      .status(&page.url, page.status_code, res.get_html_bytes_u8());
+
+    match dedupe {
+      Some(result) => {
+        page.fingerprint = Some(format!("{:016x}", result.fingerprint));
+        (page, result.duplicate.is_some())
+      }
+      _ => (page, false),
+    }
+  }
+
+  /// send the page to the javascript callback rendering the PDF first if enabled.
+  pub async fn send(
+    &self,
+    callback: &ThreadsafeFunction<NPage>,
+    res: &spider::page::Page,
+    raw_content: bool,
+  ) {
+    let (page, duplicate) = self.build(res, raw_content);
+
+    if duplicate {
+      return;
+    }
+
+    if let Some(ref normalized_url) = page.normalized_url {
+      if self.normalizer.is_duplicate(&page.url, normalized_url) {
+        return;
+      }
+    }
+
+    if self.pdf.is_active() {
+      let callback = callback.clone();
+      let render = self.pdf.render(page.url.clone());
+
+      spider::tokio::spawn(async move {
+        let mut page = page;
+        page.pdf = render.await.map(Into::into);
+        callback.call(Ok(page), ThreadsafeFunctionCallMode::NonBlocking);
+      });
+    } else {
+      callback.call(Ok(page), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  }
+}
@@ -1,4 +1,6 @@
 #![deny(clippy::all)]
+// napi needs the async methods taking `&mut self` to be unsafe.
+#![allow(clippy::missing_safety_doc)]
 
 #[macro_use]
 extern crate napi_derive;
@@ -8,16 +10,62 @@ lazy_static! {
   pub static ref BUFFER: usize = (num_cpus::get() * 20).max(88);
 }
 
+pub mod auth;
+pub mod automation;
+pub mod blocking;
+pub mod browser;
+pub mod changes;
+pub mod client;
+pub mod config;
+pub mod cookies;
+pub mod crawl_pool;
+pub mod dedupe;
+pub mod device;
+pub mod errors;
+pub mod evaluate;
+pub mod hooks;
+pub mod logger;
+pub mod login;
+pub mod metrics;
+pub mod normalize;
 pub mod npage;
 pub mod nwebsite;
+pub mod options;
 pub mod page;
+pub mod pdf;
+pub mod pool;
+pub mod proxy;
+pub mod robots;
 pub mod shortcut;
+pub mod sitemap;
+pub mod skipped;
+pub mod stats;
 pub mod website;
 
+pub use auth::{NAuth, NAuthCredentials};
+pub use automation::{NAutomationStep, NFillStep};
+pub use blocking::NResourceBlocking;
+pub use changes::NChangeTracking;
+pub use config::{NChromeIntercept, NCron, NWaitFor, NWaitForSelector, NWebsiteConfig};
+pub use cookies::NCookie;
+pub use crawl_pool::{CrawlPool, NCrawlPoolOptions, NSiteResult};
+pub use dedupe::{NDedupe, NDuplicatePage};
+pub use device::NViewport;
+pub use errors::NCrawlError;
+pub use logger::{set_logger, NLogRecord};
+pub use login::NLoginConfig;
+pub use normalize::NUrlNormalization;
 pub use npage::{page_title, NPage};
 pub use nwebsite::NWebsite;
 pub use page::Page;
+pub use pdf::NPdfOptions;
+pub use pool::{BrowserPool, NBrowserPoolOptions, NBrowserPoolStats};
+pub use proxy::{NProxyRotation, NProxyStats};
+pub use robots::{NRobotsGroup, Robots};
 pub use shortcut::crawl;
+pub use sitemap::NSitemapEntry;
+pub use skipped::NSkippedUrl;
+pub use stats::NCrawlStats;
 pub use website::Website;
 /// convert types to different types
 mod conversions;
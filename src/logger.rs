@@ -0,0 +1,135 @@
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::Env;
+use spider::lazy_static::lazy_static;
+use std::str::FromStr;
+use std::sync::{Once, RwLock};
+
+lazy_static! {
+  /// the javascript callback receiving the log records.
+  static ref LOG_CALLBACK: RwLock<Option<ThreadsafeFunction<NLogRecord>>> = RwLock::new(None);
+}
+
+/// setup the global logger once.
+static LOGGER_INIT: Once = Once::new();
+
+/// the logger forwarding the records to javascript.
+static LOGGER: JsLogger = JsLogger;
+
+/// a structured log record from the crawler.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct NLogRecord {
+  /// The level of the record: error, warn, info, debug or trace.
+  pub level: String,
+  /// The target of the record, usually the rust module that emitted it.
+  pub target: String,
+  /// The message of the record.
+  pub message: String,
+  /// The url found in the message.
+  pub url: Option<String>,
+  /// The error detail of warn and error records.
+  pub error: Option<String>,
+  /// The rust module path that emitted the record.
+  pub module_path: Option<String>,
+  /// The source file that emitted the record.
+  pub file: Option<String>,
+  /// The source line that emitted the record.
+  pub line: Option<u32>,
+}
+
+impl NLogRecord {
+  /// establish a new record from the log record.
+  pub fn new(record: &log::Record) -> NLogRecord {
+    let message = record.args().to_string();
+
+    NLogRecord {
+      level: record.level().as_str().to_lowercase(),
+      target: record.target().into(),
+      url: find_url(&message),
+      error: if record.level() <= log::Level::Warn {
+        find_error(&message)
+      } else {
+        None
+      },
+      module_path: record.module_path().map(Into::into),
+      file: record.file().map(Into::into),
+      line: record.line(),
+      message,
+    }
+  }
+}
+
+/// find the first url in the message.
+fn find_url(message: &str) -> Option<String> {
+  message
+    .split(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == ',')
+    .find(|word| word.starts_with("http://") || word.starts_with("https://"))
+    .map(|url| url.trim_end_matches([')', '.', ':']).to_string())
+}
+
+/// find the error detail following the first separator in the message.
+fn find_error(message: &str) -> Option<String> {
+  match message.split_once(": ") {
+    Some((_, error)) if !error.is_empty() => Some(error.to_string()),
+    _ => Some(message.to_string()),
+  }
+}
+
+/// the logger forwarding the records to javascript.
+struct JsLogger;
+
+impl log::Log for JsLogger {
+  fn enabled(&self, metadata: &log::Metadata) -> bool {
+    metadata.level() <= log::max_level()
+  }
+
+  fn log(&self, record: &log::Record) {
+    if self.enabled(record.metadata()) {
+      if let Ok(callback) = LOG_CALLBACK.read() {
+        if let Some(callback) = callback.as_ref() {
+          callback.call(
+            Ok(NLogRecord::new(record)),
+            ThreadsafeFunctionCallMode::NonBlocking,
+          );
+        }
+      }
+    }
+  }
+
+  fn flush(&self) {}
+}
+
+#[napi]
+/// Forward the crawler logs to the callback. The level is one of error, warn, info, debug or trace and defaults to info. Pass no callback to stop logging.
+pub fn set_logger(
+  env: Env,
+  callback: Option<ThreadsafeFunction<NLogRecord>>,
+  level: Option<String>,
+) -> napi::Result<()> {
+  LOGGER_INIT.call_once(|| {
+    let _ = log::set_logger(&LOGGER);
+  });
+
+  let callback = match callback {
+    Some(mut callback) => {
+      // the logger should not keep the process alive.
+      callback.unref(&env)?;
+      Some(callback)
+    }
+    _ => None,
+  };
+
+  let level = match callback {
+    Some(_) => log::LevelFilter::from_str(level.as_deref().unwrap_or("info"))
+      .map_err(|e| napi::Error::from_reason(e.to_string()))?,
+    _ => log::LevelFilter::Off,
+  };
+
+  if let Ok(mut log_callback) = LOG_CALLBACK.write() {
+    *log_callback = callback;
+  }
+
+  log::set_max_level(level);
+
+  Ok(())
+}
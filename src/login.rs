@@ -0,0 +1,341 @@
+use crate::browser::BrowserHandle;
+use crate::cookies::{CookieStore, NCookie};
+use crate::options::Options;
+use serde::{Deserialize, Serialize};
+use spider::lazy_static::lazy_static;
+use spider::reqwest::cookie::{CookieStore as _, Jar};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+lazy_static! {
+  static ref LINK_SELECTOR: scraper::Selector = scraper::Selector::parse("a[href]").unwrap();
+}
+
+/// the login step to run before the crawl.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NLoginConfig {
+  /// The url of the login page or form action.
+  pub url: String,
+  /// The HTTP method to submit the form with. Defaults to POST.
+  pub method: Option<String>,
+  /// The form fields by input name.
+  pub form: Option<HashMap<String, String>>,
+  /// A CSS selector that is only found on the page after a successful login.
+  pub success_selector: Option<String>,
+  /// Fill and submit the form using headless chrome instead of HTTP.
+  pub headless: Option<bool>,
+}
+
+/// the login flow shared between the website and the crawl subscriptions.
+#[derive(Default)]
+pub struct Login {
+  /// the login step.
+  pub config: Options<NLoginConfig>,
+  /// the HTTP client used to login sharing the crawl cookie jar.
+  client: RwLock<Option<spider::reqwest::Client>>,
+  /// the chrome connection url.
+  chrome_connection: RwLock<Option<String>>,
+  /// the amount of logins run to skip the logins waiting on a login that just ran.
+  runs: AtomicU64,
+  /// run one login at a time.
+  lock: spider::tokio::sync::Mutex<()>,
+  /// the urls fetched again after the session expired.
+  refetched: Mutex<HashSet<String>>,
+}
+
+impl Login {
+  /// setup the HTTP client and chrome connection from the website before the crawl.
+  pub fn prepare(&self, website: &spider::website::Website) {
+    let mut builder = spider::reqwest::Client::builder()
+      .cookie_provider(website.cookie_jar.clone())
+      .danger_accept_invalid_certs(website.configuration.accept_invalid_certs);
+
+    if let Some(ref user_agent) = website.configuration.user_agent {
+      builder = builder.user_agent(user_agent.as_str());
+    }
+
+    if let Some(ref headers) = website.configuration.headers {
+      builder = builder.default_headers(headers.inner().clone());
+    }
+
+    if let Ok(mut client) = self.client.write() {
+      *client = builder.build().ok();
+    }
+
+    if let Ok(mut chrome_connection) = self.chrome_connection.write() {
+      *chrome_connection = website.configuration.chrome_connection_url.clone();
+    }
+
+    if let Ok(mut refetched) = self.refetched.lock() {
+      refetched.clear();
+    }
+  }
+
+  /// did the page redirect back to the login url.
+  pub fn is_login_redirect(&self, page: &spider::page::Page) -> bool {
+    let login_url = match self
+      .config
+      .read(|config| config.map(|config| config.url.clone()))
+    {
+      Some(login_url) => login_url,
+      _ => return false,
+    };
+    let login_url = login_url.split('?').next().unwrap_or_default();
+
+    match page.final_redirect_destination {
+      Some(ref destination) => {
+        destination.starts_with(login_url) && !page.get_url().starts_with(login_url)
+      }
+      _ => false,
+    }
+  }
+
+  /// run the login step storing the session cookies in the jar. The login waits for a login already running instead of running again.
+  pub async fn run(&self, jar: &Arc<Jar>, cookies: &CookieStore) -> Result<(), String> {
+    let config = match self.config.get() {
+      Some(config) => config,
+      _ => return Ok(()),
+    };
+    let runs = self.runs.load(Ordering::SeqCst);
+    let _lock = self.lock.lock().await;
+
+    if self.runs.load(Ordering::SeqCst) != runs {
+      return Ok(());
+    }
+
+    let result = if config.headless.unwrap_or_default() {
+      let chrome_connection = match self.chrome_connection.read() {
+        Ok(c) => c.clone(),
+        _ => None,
+      };
+      login_chrome(&config, chrome_connection.as_deref(), jar, cookies).await
+    } else {
+      let client = match self.client.read() {
+        Ok(client) => client.clone(),
+        _ => None,
+      };
+      match client {
+        Some(client) => login_http(&config, &client, jar, cookies).await,
+        _ => Err("the login client is not setup".into()),
+      }
+    };
+
+    self.runs.fetch_add(1, Ordering::SeqCst);
+
+    result
+  }
+
+  /// fetch the page again with the new session after the login expired with the links found on the page. A page is fetched again once per run.
+  pub async fn refetch(&self, url: &str) -> Option<(spider::page::Page, Vec<String>)> {
+    let first = match self.refetched.lock() {
+      Ok(mut refetched) => refetched.insert(url.to_string()),
+      _ => false,
+    };
+
+    if !first {
+      return None;
+    }
+
+    let client = self.client.read().ok()?.clone()?;
+    let client = spider::reqwest_middleware::ClientBuilder::new(client).build();
+    let page = spider::page::Page::new_page(url, &client).await;
+
+    if self.is_login_redirect(&page) {
+      return None;
+    }
+
+    let links = page_links(&page);
+
+    Some((page, links))
+  }
+}
+
+/// the http links found on the page.
+fn page_links(page: &spider::page::Page) -> Vec<String> {
+  let base = match spider::url::Url::parse(page.get_url()) {
+    Ok(base) => base,
+    _ => return Default::default(),
+  };
+  let fragment = scraper::Html::parse_document(&page.get_html());
+
+  fragment
+    .select(&LINK_SELECTOR)
+    .filter_map(|element| base.join(element.value().attr("href")?).ok())
+    .filter(|url| url.scheme() == "http" || url.scheme() == "https")
+    .map(Into::into)
+    .collect()
+}
+
+/// quote the value as a CSS string escaping the quotes, backslashes and line breaks.
+fn css_string(value: &str) -> String {
+  let mut quoted = String::with_capacity(value.len() + 2);
+
+  quoted.push('"');
+
+  for c in value.chars() {
+    match c {
+      '"' | '\\' => {
+        quoted.push('\\');
+        quoted.push(c);
+      }
+      '\n' | '\r' | '\x0c' => quoted.push_str(&format!("\\{:x} ", c as u32)),
+      '\0' => quoted.push('\u{FFFD}'),
+      _ => quoted.push(c),
+    }
+  }
+
+  quoted.push('"');
+  quoted
+}
+
+/// check the html for the success selector.
+fn is_logged_in(html: &str, success_selector: &Option<String>) -> Result<bool, String> {
+  match success_selector {
+    Some(selector) => {
+      let selector = scraper::Selector::parse(selector).map_err(|e| format!("{e:?}"))?;
+      let fragment = scraper::Html::parse_document(html);
+      let found = fragment.select(&selector).next().is_some();
+      Ok(found)
+    }
+    _ => Ok(true),
+  }
+}
+
+/// submit the login form using HTTP.
+async fn login_http(
+  config: &NLoginConfig,
+  client: &spider::reqwest::Client,
+  jar: &Arc<Jar>,
+  cookies: &CookieStore,
+) -> Result<(), String> {
+  let form = config.form.clone().unwrap_or_default();
+  let method = config.method.as_deref().unwrap_or("POST").to_uppercase();
+
+  let request = if method == "GET" {
+    client.get(&config.url).query(&form)
+  } else {
+    let method =
+      spider::reqwest::Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+    client.request(method, &config.url).form(&form)
+  };
+
+  let res = request.send().await.map_err(|e| e.to_string())?;
+  let status = res.status();
+  let url = res.url().clone();
+  let html = res.text().await.map_err(|e| e.to_string())?;
+
+  if !status.is_success() || !is_logged_in(&html, &config.success_selector)? {
+    return Err(format!(
+      "login failed for {} with status {}",
+      config.url, status
+    ));
+  }
+
+  // the cookies set across the redirects are stored in the jar.
+  if let Some(header) = jar.cookies(&url) {
+    if let Ok(header) = header.to_str() {
+      for (name, value) in header
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+      {
+        cookies.insert(NCookie {
+          name: name.into(),
+          value: value.into(),
+          domain: url.host_str().unwrap_or_default().into(),
+          ..Default::default()
+        });
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// fill and submit the login form using headless chrome.
+async fn login_chrome(
+  config: &NLoginConfig,
+  chrome_connection: Option<&str>,
+  jar: &Arc<Jar>,
+  cookies: &CookieStore,
+) -> Result<(), String> {
+  let browser = BrowserHandle::connect(chrome_connection).await?;
+
+  let result = async {
+    let page = browser
+      .browser
+      .new_page(config.url.as_str())
+      .await
+      .map_err(|e| e.to_string())?;
+
+    let form = config.form.clone().unwrap_or_default();
+
+    for (name, value) in form.iter() {
+      page
+        .find_element(format!("[name={}]", css_string(name)))
+        .await
+        .map_err(|e| e.to_string())?
+        .click()
+        .await
+        .map_err(|e| e.to_string())?
+        .type_str(value)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    if let Some((name, _)) = form.iter().last() {
+      // submit the form the last input belongs to.
+      let selector = serde_json::to_string(&format!("[name={}]", css_string(name)))
+        .map_err(|e| e.to_string())?;
+
+      page
+        .evaluate(format!(
+          "(() => {{ const f = document.querySelector({selector}).form; f.requestSubmit ? f.requestSubmit() : f.submit(); }})()"
+        ))
+        .await
+        .map_err(|e| e.to_string())?;
+      page
+        .wait_for_navigation()
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    let html = page.content().await.map_err(|e| e.to_string())?;
+
+    if !is_logged_in(&html, &config.success_selector)? {
+      return Err(format!("login failed for {}", config.url));
+    }
+
+    for cookie in page.get_cookies().await.map_err(|e| e.to_string())? {
+      let cookie = NCookie {
+        name: cookie.name,
+        value: cookie.value,
+        domain: cookie.domain,
+        path: Some(cookie.path),
+        expires: if cookie.session {
+          None
+        } else {
+          Some(cookie.expires)
+        },
+        secure: Some(cookie.secure),
+        http_only: Some(cookie.http_only),
+      };
+      if let Some(url) = cookie.url() {
+        jar.add_cookie_str(&cookie.to_set_cookie(), &url);
+      }
+      cookies.insert(cookie);
+    }
+
+    // the tab stays open on a connected browser otherwise.
+    let _ = page.close().await;
+
+    Ok(())
+  }
+  .await;
+
+  browser.close().await;
+
+  result
+}
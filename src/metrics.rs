@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// the upper bounds in seconds of the fetch latency histogram.
+const LATENCY_BUCKETS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// the cumulative crawl metrics for a website. The metrics are never reset to follow the Prometheus counter semantics.
+#[derive(Default)]
+pub struct CrawlMetrics {
+  /// the amount of pages fetched.
+  fetches: AtomicU64,
+  /// the amount of pages that returned an error status code.
+  errors: AtomicU64,
+  /// the amount of pages flagged to retry.
+  retries: AtomicU64,
+  /// the amount of pages returned from the HTTP cache.
+  cache_hits: AtomicU64,
+  /// the amount of bytes downloaded.
+  bytes: AtomicU64,
+  /// the amount of pages per HTTP status code.
+  status_codes: Mutex<HashMap<u16, u64>>,
+  /// the fetch latency histogram bucket counts, sum in seconds and count.
+  latency: Mutex<([u64; LATENCY_BUCKETS.len()], f64, u64)>,
+}
+
+impl CrawlMetrics {
+  /// record a page that was fetched.
+  pub fn record(&self, page: &spider::page::Page) {
+    let status_code = page.status_code;
+
+    self.fetches.fetch_add(1, Ordering::Relaxed);
+    self
+      .bytes
+      .fetch_add(page.get_html_bytes_u8().len() as u64, Ordering::Relaxed);
+
+    if !status_code.is_success() && !status_code.is_redirection() {
+      self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    if page.should_retry {
+      self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    if let Some(ref headers) = page.headers {
+      let cache_hit = headers
+        .get("x-cache")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("hit"))
+        .unwrap_or_default();
+
+      if cache_hit {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+      }
+    }
+
+    if let Ok(mut status_codes) = self.status_codes.lock() {
+      *status_codes.entry(status_code.as_u16()).or_default() += 1;
+    }
+
+    let elapsed = page.get_duration_elapsed().as_secs_f64();
+
+    if let Ok(mut latency) = self.latency.lock() {
+      for (i, bound) in LATENCY_BUCKETS.iter().enumerate() {
+        if elapsed <= *bound {
+          latency.0[i] += 1;
+        }
+      }
+      latency.1 += elapsed;
+      latency.2 += 1;
+    }
+  }
+
+  /// render the metrics using the Prometheus text exposition format.
+  pub fn render(&self, website: &str) -> String {
+    let mut text = String::new();
+    let label = format!("website=\"{}\"", escape_label(website));
+
+    let counters = [
+      (
+        "spider_fetches_total",
+        "The amount of pages fetched.",
+        &self.fetches,
+      ),
+      (
+        "spider_errors_total",
+        "The amount of pages that returned an error status code.",
+        &self.errors,
+      ),
+      (
+        "spider_retries_total",
+        "The amount of pages flagged to retry.",
+        &self.retries,
+      ),
+      (
+        "spider_cache_hits_total",
+        "The amount of pages returned from the HTTP cache.",
+        &self.cache_hits,
+      ),
+      (
+        "spider_downloaded_bytes_total",
+        "The amount of bytes downloaded.",
+        &self.bytes,
+      ),
+    ];
+
+    for (name, help, value) in counters {
+      let _ = writeln!(text, "# HELP {name} {help}");
+      let _ = writeln!(text, "# TYPE {name} counter");
+      let _ = writeln!(text, "{name}{{{label}}} {}", value.load(Ordering::Relaxed));
+    }
+
+    let _ = writeln!(
+      text,
+      "# HELP spider_responses_total The amount of pages per HTTP status code."
+    );
+    let _ = writeln!(text, "# TYPE spider_responses_total counter");
+
+    if let Ok(status_codes) = self.status_codes.lock() {
+      let mut status_codes = status_codes.iter().collect::<Vec<_>>();
+      status_codes.sort();
+
+      for (code, count) in status_codes {
+        let _ = writeln!(
+          text,
+          "spider_responses_total{{{label},code=\"{code}\"}} {count}"
+        );
+      }
+    }
+
+    let _ = writeln!(
+      text,
+      "# HELP spider_fetch_duration_seconds The time taken to fetch a page."
+    );
+    let _ = writeln!(text, "# TYPE spider_fetch_duration_seconds histogram");
+
+    if let Ok(latency) = self.latency.lock() {
+      for (i, bound) in LATENCY_BUCKETS.iter().enumerate() {
+        let _ = writeln!(
+          text,
+          "spider_fetch_duration_seconds_bucket{{{label},le=\"{bound}\"}} {}",
+          latency.0[i]
+        );
+      }
+      let _ = writeln!(
+        text,
+        "spider_fetch_duration_seconds_bucket{{{label},le=\"+Inf\"}} {}",
+        latency.2
+      );
+      let _ = writeln!(
+        text,
+        "spider_fetch_duration_seconds_sum{{{label}}} {}",
+        latency.1
+      );
+      let _ = writeln!(
+        text,
+        "spider_fetch_duration_seconds_count{{{label}}} {}",
+        latency.2
+      );
+    }
+
+    text
+  }
+}
+
+/// escape a Prometheus label value.
+fn escape_label(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\n")
+}
+
+/// serve the metrics over HTTP on the listener until the task is aborted.
+pub async fn serve(
+  listener: napi::tokio::net::TcpListener,
+  crawl_stats: std::sync::Arc<crate::stats::CrawlStats>,
+  website: String,
+) {
+  use napi::tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+  while let Ok((mut stream, _)) = listener.accept().await {
+    let crawl_stats = crawl_stats.clone();
+    let website = website.clone();
+
+    spider::tokio::spawn(async move {
+      // the request is not routed, every path returns the metrics.
+      let mut buf = [0; 1024];
+      let _ = stream.read(&mut buf).await;
+      let body = crawl_stats.metrics.render(&website);
+      let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+      );
+      let _ = stream.write_all(response.as_bytes()).await;
+      let _ = stream.shutdown().await;
+    });
+  }
+}
@@ -0,0 +1,207 @@
+use crate::options::Options;
+use serde::{Deserialize, Serialize};
+use spider::lazy_static::lazy_static;
+use spider::url::Url;
+use spider::CaseInsensitiveString;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+lazy_static! {
+  static ref CANONICAL_SELECTOR: scraper::Selector =
+    scraper::Selector::parse("link[rel~=canonical][href]").unwrap();
+}
+
+/// the url normalization applied to the links before they are crawled.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NUrlNormalization {
+  /// The query params to remove. A trailing * removes the params starting with the prefix like utm_*.
+  pub strip_params: Option<Vec<String>>,
+  /// Sort the query params by name.
+  pub sort_query: Option<bool>,
+  /// Lowercase the host. The WHATWG url parsing already lowercases the hosts of the links.
+  pub lowercase_host: Option<bool>,
+  /// Add the trailing slash to the paths when true or remove it when false. Keeps the path as found when not set.
+  pub trailing_slash: Option<bool>,
+  /// Use the canonical link of the page as the normalized url. Pages with a canonical url already sent are skipped.
+  pub respect_canonical: Option<bool>,
+}
+
+/// normalize the links before they enter the visited set and skip the duplicate pages.
+#[derive(Default)]
+pub struct UrlNormalizer {
+  /// the normalization options.
+  pub options: Options<NUrlNormalization>,
+  /// the first url found by normalized url.
+  originals: Mutex<HashMap<String, String>>,
+  /// the page url sent by normalized url.
+  sent: Mutex<HashMap<String, String>>,
+}
+
+impl UrlNormalizer {
+  /// clear the urls of the last run and rewrite the links found with the normalized url.
+  pub fn prepare(self: &Arc<Self>, website: &mut spider::website::Website) {
+    if let Ok(mut originals) = self.originals.lock() {
+      originals.clear();
+    }
+    if let Ok(mut sent) = self.sent.lock() {
+      sent.clear();
+    }
+
+    if self.options.is_enabled() {
+      let normalizer = self.clone();
+
+      website.set_on_link_find(move |link: CaseInsensitiveString, html| {
+        let normalized = normalizer.normalize(link.as_ref());
+
+        if normalized != link.as_ref() {
+          if let Ok(mut originals) = normalizer.originals.lock() {
+            originals
+              .entry(normalized.clone())
+              .or_insert_with(|| link.as_ref().to_string());
+          }
+        }
+
+        (normalized.into(), html)
+      });
+    } else {
+      website.with_on_link_find_callback(None);
+    }
+  }
+
+  /// normalize the url with the options.
+  pub fn normalize(&self, url: &str) -> String {
+    self.options.read(|options| match options {
+      Some(options) => normalize_url(options, url),
+      _ => url.to_string(),
+    })
+  }
+
+  /// the url found before the normalization.
+  pub fn original(&self, url: &str) -> String {
+    match self.originals.lock() {
+      Ok(originals) => originals
+        .get(url)
+        .cloned()
+        .unwrap_or_else(|| url.to_string()),
+      _ => url.to_string(),
+    }
+  }
+
+  /// the normalized url of the page using the canonical link when respected.
+  pub fn page_url(&self, url: &str, html: &str) -> String {
+    let respect_canonical = self.options.read(|options| {
+      options
+        .and_then(|options| options.respect_canonical)
+        .unwrap_or_default()
+    });
+
+    // skip parsing the html when the canonical link is not used.
+    match respect_canonical
+      .then(|| canonical_url(url, html))
+      .flatten()
+    {
+      Some(canonical) => self.normalize(&canonical),
+      _ => self.normalize(url),
+    }
+  }
+
+  /// is a different page already sent with the normalized url.
+  pub fn is_duplicate(&self, url: &str, normalized_url: &str) -> bool {
+    match self.sent.lock() {
+      Ok(mut sent) => sent
+        .entry(normalized_url.to_string())
+        .or_insert_with(|| url.to_string())
+        .as_str()
+        .ne(url),
+      _ => false,
+    }
+  }
+}
+
+/// the canonical link of the page on the same host.
+fn canonical_url(url: &str, html: &str) -> Option<String> {
+  let base = Url::parse(url).ok()?;
+  let fragment = scraper::Html::parse_document(html);
+  let href = fragment
+    .select(&CANONICAL_SELECTOR)
+    .next()?
+    .value()
+    .attr("href")?;
+  let canonical = base.join(href.trim()).ok()?;
+
+  if canonical.host_str()?.eq_ignore_ascii_case(base.host_str()?) {
+    Some(canonical.into())
+  } else {
+    None
+  }
+}
+
+/// normalize the url removing the fragment and applying the options. The host is always lowercased by the url parser.
+fn normalize_url(options: &NUrlNormalization, url: &str) -> String {
+  let mut parsed = match Url::parse(url) {
+    Ok(parsed) => parsed,
+    _ => return url.to_string(),
+  };
+
+  parsed.set_fragment(None);
+
+  let strip_params = options.strip_params.as_deref().unwrap_or_default();
+  let sort_query = options.sort_query.unwrap_or_default();
+
+  if parsed.query().is_some() && (sort_query || !strip_params.is_empty()) {
+    let mut pairs: Vec<(String, String)> = parsed
+      .query_pairs()
+      .into_owned()
+      .filter(|(name, _)| !strip_params.iter().any(|param| is_param_match(param, name)))
+      .collect();
+
+    if sort_query {
+      pairs.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+
+    if pairs.is_empty() {
+      parsed.set_query(None);
+    } else {
+      parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+  }
+
+  match options.trailing_slash {
+    Some(true) => {
+      let path = parsed.path();
+      // keep the file paths like /index.html.
+      let is_file = path
+        .rsplit('/')
+        .next()
+        .is_some_and(|last| last.contains('.'));
+
+      if !path.ends_with('/') && !is_file {
+        let path = format!("{path}/");
+        parsed.set_path(&path);
+      }
+    }
+    Some(false) => {
+      let path = parsed.path();
+
+      if path.len() > 1 && path.ends_with('/') {
+        let path = path.trim_end_matches('/').to_string();
+        parsed.set_path(&path);
+      }
+    }
+    _ => (),
+  }
+
+  parsed.into()
+}
+
+/// does the query param name match the pattern. A trailing * matches the prefix.
+fn is_param_match(pattern: &str, name: &str) -> bool {
+  match pattern.strip_suffix('*') {
+    Some(prefix) => name
+      .to_ascii_lowercase()
+      .starts_with(&prefix.to_ascii_lowercase()),
+    _ => name.eq_ignore_ascii_case(pattern),
+  }
+}
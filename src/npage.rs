@@ -1,3 +1,4 @@
+use crate::evaluate::{find_evaluated, take_evaluated};
 use napi::bindgen_prelude::Buffer;
 use spider::{lazy_static::lazy_static, reqwest::header::HeaderMap};
 use std::collections::HashMap;
@@ -22,6 +23,23 @@ pub struct NPage {
   pub headers: Option<HashMap<String, String>>,
   /// The links found on the page. Requires the website.builder method website.with_subscription_return_page_links to be set to true.
   pub links: Option<Vec<String>>,
+  /// The JSON result of the expression evaluated on the page in headless mode. Requires the website.builder method website.with_evaluate_scripts to be set.
+  pub evaluated: Option<serde_json::Value>,
+  /// The screenshot bytes. Requires the website.builder method website.with_screenshot to be set with bytes enabled.
+  pub screenshot: Option<Buffer>,
+  /// The PDF render of the page. Requires the website.builder method website.with_pdf to be set.
+  pub pdf: Option<Buffer>,
+  /// The amount of resources referenced by the page html matching the blocking rules. The resources requested by scripts are not counted. Requires the website.builder method website.with_resource_blocking to be set.
+  pub blocked_resources: Option<u32>,
+  /// The url found before the normalization. Requires the website.builder method website.with_url_normalization to be set.
+  pub original_url: Option<String>,
+  /// The normalized url using the canonical link when respected. Requires the website.builder method website.with_url_normalization to be set.
+  pub normalized_url: Option<String>,
+  /// The SimHash fingerprint of the page text as hex. Requires the website.builder method website.with_dedupe to be set.
+  pub fingerprint: Option<String>,
+  /// The change of the page since the last run. Removed pages are sent at the end of the run. Requires the website.builder method website.with_change_tracking to be set.
+  #[napi(ts_type = "'new' | 'changed' | 'unchanged' | 'removed'")]
+  pub change_status: Option<String>,
 }
 
 #[napi]
@@ -32,34 +50,39 @@ pub fn page_title(page: NPage) -> String {
 
 #[napi]
 impl NPage {
-  /// establish a new page
-  pub fn new(res: &spider::page::Page, raw: bool) -> NPage {
+  /// establish a new page. The evaluated result is only looked up when the website evaluates scripts.
+  pub fn new(res: &spider::page::Page, raw: bool, evaluate: bool) -> NPage {
+    let (content, evaluated) = match (raw, evaluate) {
+      (true, true) => (Default::default(), find_evaluated(res.get_html_bytes_u8())),
+      (true, false) => (Default::default(), None),
+      (false, true) => take_evaluated(res.get_html()),
+      (false, false) => (res.get_html(), None),
+    };
+
     NPage {
       url: res.get_url().into(),
       status_code: res.status_code.as_u16(),
-      content: if raw {
-        Default::default()
-      } else {
-        res.get_html()
-      },
+      content,
       raw_content: if raw {
         Some(res.get_html_bytes_u8().into())
       } else {
         None
       },
-      headers: match res.headers {
-        Some(ref headers) => Some(header_map_to_hash_map(headers)),
-        _ => None,
-      },
-      links: match res.page_links {
-        Some(ref links) => Some(
-          links
-            .iter()
-            .map(|link| link.as_ref().to_string())
-            .collect::<Vec<String>>(),
-        ),
-        _ => None,
-      },
+      headers: res.headers.as_ref().map(header_map_to_hash_map),
+      links: res.page_links.as_ref().map(|links| {
+        links
+          .iter()
+          .map(|link| link.as_ref().to_string())
+          .collect::<Vec<String>>()
+      }),
+      evaluated,
+      screenshot: res.screenshot_bytes.clone().map(Into::into),
+      pdf: None,
+      blocked_resources: None,
+      original_url: None,
+      normalized_url: None,
+      fingerprint: None,
+      change_status: None,
     }
   }
 
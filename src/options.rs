@@ -0,0 +1,37 @@
+use std::sync::RwLock;
+
+/// the options of a crawl feature enabled when set.
+pub struct Options<T>(RwLock<Option<T>>);
+
+impl<T> Default for Options<T> {
+  fn default() -> Self {
+    Options(RwLock::new(None))
+  }
+}
+
+impl<T: Clone> Options<T> {
+  /// set the options or disable the feature with none.
+  pub fn set(&self, options: Option<T>) {
+    if let Ok(mut o) = self.0.write() {
+      *o = options;
+    }
+  }
+
+  /// the options.
+  pub fn get(&self) -> Option<T> {
+    self.read(|options| options.cloned())
+  }
+
+  /// is the feature enabled.
+  pub fn is_enabled(&self) -> bool {
+    self.read(|options| options.is_some())
+  }
+
+  /// read the options without cloning them.
+  pub fn read<R>(&self, f: impl FnOnce(Option<&T>) -> R) -> R {
+    match self.0.read() {
+      Ok(options) => f(options.as_ref()),
+      _ => f(None),
+    }
+  }
+}
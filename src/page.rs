@@ -35,16 +35,17 @@ impl Page {
   /// get the page content
   pub async unsafe fn fetch(&mut self) -> &Self {
     use spider::{
-      lazy_static::lazy_static, reqwest::Client, reqwest_middleware::ClientWithMiddleware,
-      ClientBuilder,
+      lazy_static::lazy_static,
+      reqwest::Client,
+      reqwest_middleware::{ClientBuilder, ClientWithMiddleware},
     };
     lazy_static! {
       /// top level single page client to re-use.
       pub static ref PAGE_CLIENT: ClientWithMiddleware = {
         let reqwest_client = Client::builder().build().unwrap_or_default();
-        let client = ClientBuilder::new(reqwest_client).build();
 
-        client
+
+        ClientBuilder::new(reqwest_client).build()
       };
     }
     let page = spider::page::Page::new_page(&self.url, &PAGE_CLIENT).await;
@@ -64,7 +65,7 @@ impl Page {
     match &self.selectors {
       Some(selectors) => match &self.inner {
         Some(inner) => {
-          let links = inner.clone().links(&selectors, &None).await;
+          let links = inner.clone().links(selectors, &None).await;
           links
             .into_iter()
             .map(|i| i.as_ref().to_string())
@@ -0,0 +1,201 @@
+use crate::browser::BrowserHandle;
+use crate::options::Options;
+use serde::{Deserialize, Serialize};
+use spider::chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams;
+use spider::tokio::sync::{Mutex, Notify, Semaphore};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// the amount of pages rendered at the same time.
+const PDF_CONCURRENCY: usize = 4;
+
+/// the PDF render options.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NPdfOptions {
+  /// Use the landscape orientation. Defaults to false.
+  pub landscape: Option<bool>,
+  /// Print the background graphics. Defaults to false.
+  pub print_background: Option<bool>,
+  /// The scale of the page rendering. Defaults to 1.
+  pub scale: Option<f64>,
+  /// The paper width in inches. Defaults to 8.5.
+  pub paper_width: Option<f64>,
+  /// The paper height in inches. Defaults to 11.
+  pub paper_height: Option<f64>,
+  /// Prefer the page size defined by css. Defaults to false.
+  pub prefer_css_page_size: Option<bool>,
+}
+
+/// render the pages of the headless crawls as PDF using the chrome of the crawl.
+pub struct PdfRenderer {
+  /// the render options.
+  pub options: Options<NPdfOptions>,
+  /// are the pages of the current crawl rendered.
+  active: AtomicBool,
+  /// the chrome connection url of the crawl.
+  chrome_connection: RwLock<Option<String>>,
+  /// the chrome of the crawl shared by the renders.
+  browser: Mutex<Option<Arc<BrowserHandle>>>,
+  /// limit the renders running at the same time.
+  permits: Arc<Semaphore>,
+  /// the renders started and not finished yet.
+  pending: AtomicUsize,
+  /// notified when a render finishes.
+  rendered: Notify,
+}
+
+impl Default for PdfRenderer {
+  fn default() -> Self {
+    PdfRenderer {
+      options: Default::default(),
+      active: Default::default(),
+      chrome_connection: Default::default(),
+      browser: Default::default(),
+      permits: Arc::new(Semaphore::new(PDF_CONCURRENCY)),
+      pending: Default::default(),
+      rendered: Default::default(),
+    }
+  }
+}
+
+impl PdfRenderer {
+  /// render the pages of a headless crawl. The crawl uses a chrome instance launched for the renders when the website has no chrome connection.
+  pub async fn prepare(
+    &self,
+    website: &mut spider::website::Website,
+    headless: bool,
+  ) -> Result<(), String> {
+    let active = headless && self.options.is_enabled();
+
+    self.active.store(active, Ordering::Relaxed);
+
+    if !active {
+      return Ok(());
+    }
+
+    if website.configuration.chrome_connection_url.is_none() {
+      let browser = BrowserHandle::launch().await?;
+
+      website
+        .configuration
+        .with_chrome_connection(Some(browser.websocket_address()));
+
+      if let Some(browser) = self.browser.lock().await.replace(Arc::new(browser)) {
+        close_browser(browser).await;
+      }
+    }
+
+    if let Ok(mut chrome_connection) = self.chrome_connection.write() {
+      *chrome_connection = website.configuration.chrome_connection_url.clone();
+    }
+
+    Ok(())
+  }
+
+  /// are the pages of the current crawl rendered.
+  pub fn is_active(&self) -> bool {
+    self.active.load(Ordering::Relaxed)
+  }
+
+  /// render the url as PDF waiting for a render slot. The render is counted right away so the browser stays open until it finishes.
+  pub fn render(self: &Arc<Self>, url: String) -> impl Future<Output = Option<Vec<u8>>> {
+    let renderer = self.clone();
+
+    renderer.pending.fetch_add(1, Ordering::SeqCst);
+
+    async move {
+      let pdf = match renderer.permits.acquire().await {
+        Ok(_permit) => renderer.render_page(&url).await,
+        _ => None,
+      };
+
+      renderer.pending.fetch_sub(1, Ordering::SeqCst);
+      renderer.rendered.notify_waiters();
+
+      pdf
+    }
+  }
+
+  /// render the url as PDF in a new tab of the crawl chrome.
+  async fn render_page(&self, url: &str) -> Option<Vec<u8>> {
+    let options = self.options.get()?;
+
+    // the lock is only held to connect the browser so the renders navigate at the same time.
+    let browser = {
+      let mut browser = self.browser.lock().await;
+
+      if browser.is_none() {
+        let chrome_connection = match self.chrome_connection.read() {
+          Ok(c) => c.clone(),
+          _ => None,
+        };
+        match BrowserHandle::connect(chrome_connection.as_deref()).await {
+          Ok(handle) => *browser = Some(Arc::new(handle)),
+          Err(e) => {
+            log::warn!("pdf browser failed: {e}");
+            return None;
+          }
+        }
+      }
+
+      browser.clone()?
+    };
+
+    let page = match browser.browser.new_page(url).await {
+      Ok(page) => page,
+      Err(e) => {
+        log::warn!("pdf render failed for {url}: {e}");
+        return None;
+      }
+    };
+
+    let pdf = page
+      .pdf(PrintToPdfParams {
+        landscape: options.landscape,
+        print_background: options.print_background,
+        scale: options.scale,
+        paper_width: options.paper_width,
+        paper_height: options.paper_height,
+        prefer_css_page_size: options.prefer_css_page_size,
+        ..Default::default()
+      })
+      .await;
+
+    let _ = page.close().await;
+
+    match pdf {
+      Ok(pdf) => Some(pdf),
+      Err(e) => {
+        log::warn!("pdf render failed for {url}: {e}");
+        None
+      }
+    }
+  }
+
+  /// wait for the renders started and close the browser.
+  pub async fn finish(&self) {
+    loop {
+      let rendered = self.rendered.notified();
+
+      if self.pending.load(Ordering::SeqCst) == 0 {
+        break;
+      }
+
+      rendered.await;
+    }
+
+    if let Some(browser) = self.browser.lock().await.take() {
+      close_browser(browser).await;
+    }
+  }
+}
+
+/// close the browser when no render still holds it, the browser is dropped with the last render otherwise.
+async fn close_browser(browser: Arc<BrowserHandle>) {
+  if let Ok(browser) = Arc::try_unwrap(browser) {
+    browser.close().await;
+  }
+}
@@ -0,0 +1,288 @@
+use crate::browser::BrowserHandle;
+use crate::stats::CrawlStats;
+use spider::tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// the browser pool options.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct NBrowserPoolOptions {
+  /// The amount of chrome instances. Defaults to 1.
+  pub browsers: Option<u32>,
+  /// The amount of tabs open at the same time per chrome instance. Defaults to 4.
+  pub tabs_per_browser: Option<u32>,
+  /// Restart the chrome instance after serving the amount of pages. The restart happens once the crawl using it finishes. Defaults to 0 to never recycle.
+  pub recycle_after: Option<u32>,
+}
+
+/// the browser pool statistics.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct NBrowserPoolStats {
+  /// The amount of chrome instances in the pool.
+  pub browsers: u32,
+  /// The amount of chrome instances running.
+  pub running: u32,
+  /// The amount of chrome instances used by a crawl.
+  pub busy: u32,
+  /// The amount of crawls waiting for a chrome instance.
+  pub waiting: u32,
+  /// The amount of tabs allowed per chrome instance.
+  pub tabs_per_browser: u32,
+  /// The amount of pages served by the pool.
+  pub pages_served: u32,
+  /// The amount of chrome instances restarted after serving the recycle amount of pages.
+  pub recycled: u32,
+  /// The amount of chrome instances restarted after a crash.
+  pub restarts: u32,
+}
+
+/// a chrome instance slot of the pool.
+#[derive(Default)]
+struct PoolSlot {
+  /// the chrome instance launched on first use.
+  browser: Option<BrowserHandle>,
+  /// is the slot used by a crawl.
+  busy: bool,
+  /// the pages served since the chrome instance started.
+  pages: u32,
+}
+
+/// the chrome instances shared by the crawls.
+pub struct Pool {
+  /// the amount of tabs allowed per chrome instance.
+  tabs_per_browser: u32,
+  /// the amount of pages before a chrome instance restarts.
+  recycle_after: u32,
+  /// the chrome instance slots.
+  slots: Mutex<Vec<PoolSlot>>,
+  /// the free slots.
+  permits: Arc<Semaphore>,
+  /// the crawls waiting for a slot.
+  waiting: AtomicU32,
+  /// the pages served by the pool.
+  pages_served: AtomicU32,
+  /// the chrome instances recycled.
+  recycled: AtomicU32,
+  /// the chrome instances restarted after a crash.
+  restarts: AtomicU32,
+}
+
+impl Pool {
+  /// create the pool. The chrome instances launch on first use.
+  pub fn new(options: NBrowserPoolOptions) -> Pool {
+    let browsers = options.browsers.unwrap_or(1).max(1);
+
+    Pool {
+      tabs_per_browser: options.tabs_per_browser.unwrap_or(4).max(1),
+      recycle_after: options.recycle_after.unwrap_or_default(),
+      slots: Mutex::new((0..browsers).map(|_| PoolSlot::default()).collect()),
+      permits: Arc::new(Semaphore::new(browsers as usize)),
+      waiting: Default::default(),
+      pages_served: Default::default(),
+      recycled: Default::default(),
+      restarts: Default::default(),
+    }
+  }
+
+  /// wait for a free chrome instance launching or restarting it as needed.
+  pub async fn acquire(
+    self: &Arc<Self>,
+    crawl_stats: Arc<CrawlStats>,
+  ) -> Result<BrowserLease, String> {
+    self.waiting.fetch_add(1, Ordering::Relaxed);
+    let permit = self.permits.clone().acquire_owned().await;
+    self.waiting.fetch_sub(1, Ordering::Relaxed);
+    let permit = permit.map_err(|e| e.to_string())?;
+
+    let (slot, browser) = {
+      let mut slots = self.slots.lock().map_err(|e| e.to_string())?;
+      let slot = slots
+        .iter()
+        .position(|slot| !slot.busy)
+        .ok_or("the browser pool has no free slot")?;
+      slots[slot].busy = true;
+      (slot, slots[slot].browser.take())
+    };
+
+    let browser = match browser {
+      Some(browser) if browser.is_running() => Ok(browser),
+      browser => {
+        if let Some(browser) = browser {
+          log::warn!("browser pool restarting a crashed chrome instance");
+          self.restarts.fetch_add(1, Ordering::Relaxed);
+          browser.close().await;
+          if let Ok(mut slots) = self.slots.lock() {
+            slots[slot].pages = 0;
+          }
+        }
+        BrowserHandle::launch().await
+      }
+    };
+
+    match browser {
+      Ok(browser) => Ok(BrowserLease {
+        pool: self.clone(),
+        slot,
+        websocket_address: browser.websocket_address(),
+        browser: Some(browser),
+        crawl_stats,
+        _permit: permit,
+      }),
+      Err(e) => {
+        if let Ok(mut slots) = self.slots.lock() {
+          slots[slot].busy = false;
+        }
+        Err(format!("browser pool failed to launch chrome: {e}"))
+      }
+    }
+  }
+
+  /// return the chrome instance to the slot recycling it after the page limit.
+  fn release(&self, slot: usize, browser: Option<BrowserHandle>, pages: u32) {
+    self.pages_served.fetch_add(pages, Ordering::Relaxed);
+
+    let retired = match self.slots.lock() {
+      Ok(mut slots) => {
+        let slot = &mut slots[slot];
+        slot.busy = false;
+        slot.pages += pages;
+
+        if self.recycle_after > 0 && slot.pages >= self.recycle_after {
+          self.recycled.fetch_add(1, Ordering::Relaxed);
+          slot.pages = 0;
+          browser
+        } else {
+          slot.browser = browser;
+          None
+        }
+      }
+      _ => browser,
+    };
+
+    if let Some(browser) = retired {
+      napi::bindgen_prelude::spawn(browser.close());
+    }
+  }
+
+  /// close the chrome instances that are not used by a crawl.
+  pub async fn close(&self) {
+    let browsers: Vec<BrowserHandle> = match self.slots.lock() {
+      Ok(mut slots) => slots
+        .iter_mut()
+        .filter_map(|slot| {
+          slot.pages = 0;
+          slot.browser.take()
+        })
+        .collect(),
+      _ => Default::default(),
+    };
+
+    for browser in browsers {
+      browser.close().await;
+    }
+  }
+
+  /// get the pool statistics.
+  pub fn stats(&self) -> NBrowserPoolStats {
+    let (browsers, running, busy) = match self.slots.lock() {
+      Ok(slots) => (
+        slots.len() as u32,
+        slots
+          .iter()
+          .filter(|slot| slot.busy || slot.browser.as_ref().is_some_and(|b| b.is_running()))
+          .count() as u32,
+        slots.iter().filter(|slot| slot.busy).count() as u32,
+      ),
+      _ => Default::default(),
+    };
+
+    NBrowserPoolStats {
+      browsers,
+      running,
+      busy,
+      waiting: self.waiting.load(Ordering::Relaxed),
+      tabs_per_browser: self.tabs_per_browser,
+      pages_served: self.pages_served.load(Ordering::Relaxed),
+      recycled: self.recycled.load(Ordering::Relaxed),
+      restarts: self.restarts.load(Ordering::Relaxed),
+    }
+  }
+}
+
+/// a chrome instance used by one crawl. The instance returns to the pool when the lease drops.
+pub struct BrowserLease {
+  /// the pool owning the chrome instance.
+  pool: Arc<Pool>,
+  /// the slot of the chrome instance.
+  slot: usize,
+  /// the chrome instance.
+  browser: Option<BrowserHandle>,
+  /// the websocket url of the chrome instance.
+  websocket_address: String,
+  /// the stats of the crawl counting the pages served.
+  crawl_stats: Arc<CrawlStats>,
+  /// the pool slot permit.
+  _permit: OwnedSemaphorePermit,
+}
+
+impl BrowserLease {
+  /// point the website at the chrome instance limiting the concurrency to the tabs allowed.
+  pub fn apply(&self, website: &mut spider::website::Website) {
+    let tabs = self.pool.tabs_per_browser as usize;
+    let concurrency_limit = website.configuration.concurrency_limit;
+
+    website
+      .configuration
+      .with_chrome_connection(Some(self.websocket_address.clone()));
+    website.configuration.with_concurrency_limit(Some(
+      concurrency_limit.map_or(tabs, |limit| limit.min(tabs)),
+    ));
+  }
+}
+
+impl Drop for BrowserLease {
+  fn drop(&mut self) {
+    self.pool.release(
+      self.slot,
+      self.browser.take(),
+      self.crawl_stats.pages_fetched(),
+    );
+  }
+}
+
+/// a pool of local chrome instances shared by the websites for headless crawls.
+#[napi]
+pub struct BrowserPool {
+  /// the pool shared with the websites.
+  inner: Arc<Pool>,
+}
+
+#[napi]
+impl BrowserPool {
+  #[napi(constructor)]
+  /// create a new browser pool. The chrome instances launch on first use.
+  pub fn new(options: Option<NBrowserPoolOptions>) -> Self {
+    BrowserPool {
+      inner: Arc::new(Pool::new(options.unwrap_or_default())),
+    }
+  }
+
+  #[napi]
+  /// get the pool statistics.
+  pub fn get_stats(&self) -> NBrowserPoolStats {
+    self.inner.stats()
+  }
+
+  #[napi]
+  /// close the chrome instances that are not used by a crawl.
+  pub async fn close(&self) {
+    self.inner.close().await
+  }
+
+  /// the pool shared with the websites.
+  pub fn pool(&self) -> Arc<Pool> {
+    self.inner.clone()
+  }
+}
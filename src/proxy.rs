@@ -0,0 +1,342 @@
+use crate::options::Options;
+use serde::{Deserialize, Serialize};
+use spider::reqwest::{Request, Response, StatusCode};
+use spider::reqwest_middleware::{Middleware, Next};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// how the proxies are chosen for the requests.
+#[napi(object)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NProxyRotation {
+  /// The rotation strategy. Defaults to round-robin.
+  #[napi(ts_type = "'round-robin' | 'random' | 'sticky-per-host' | 'least-failures'")]
+  pub strategy: Option<String>,
+  /// The consecutive failures before a proxy is ejected. Defaults to 3.
+  pub max_failures: Option<u32>,
+  /// The time in milliseconds an ejected proxy is not used. Defaults to 60000.
+  pub eject_duration: Option<u32>,
+}
+
+/// the health of a proxy during the crawl.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct NProxyStats {
+  /// The proxy url.
+  pub proxy: String,
+  /// The amount of requests sent using the proxy.
+  pub requests: u32,
+  /// The amount of requests that failed using the proxy.
+  pub failures: u32,
+  /// The amount of times the proxy was ejected.
+  pub ejections: u32,
+  /// Is the proxy currently ejected.
+  pub ejected: bool,
+  /// The average request duration in milliseconds.
+  pub average_latency: f64,
+}
+
+/// the health of a proxy.
+#[derive(Default)]
+struct ProxyHealth {
+  /// the amount of requests sent.
+  requests: u32,
+  /// the amount of requests failed.
+  failures: u32,
+  /// the failures in a row.
+  consecutive_failures: u32,
+  /// the amount of ejections.
+  ejections: u32,
+  /// the proxy is not used until.
+  ejected_until: Option<Instant>,
+  /// the total request duration.
+  latency: Duration,
+}
+
+/// a proxy with its own client.
+struct ProxyEntry {
+  /// the proxy url.
+  addr: String,
+  /// the client sending the requests through the proxy.
+  client: spider::Client,
+  /// the health of the proxy.
+  health: Mutex<ProxyHealth>,
+}
+
+impl ProxyEntry {
+  /// is the proxy ejected.
+  fn is_ejected(&self, now: Instant) -> bool {
+    match self.health.lock() {
+      Ok(health) => health.ejected_until.is_some_and(|until| until > now),
+      _ => false,
+    }
+  }
+
+  /// the amount of failures.
+  fn failures(&self) -> u32 {
+    match self.health.lock() {
+      Ok(health) => health.failures,
+      _ => 0,
+    }
+  }
+}
+
+/// the proxies rotated for the requests.
+#[derive(Default)]
+pub struct ProxyRotation {
+  /// the rotation options.
+  pub options: Options<NProxyRotation>,
+  /// the proxy urls.
+  proxies: RwLock<Vec<String>>,
+  /// the proxies with their clients for the crawl.
+  pool: RwLock<Arc<Vec<ProxyEntry>>>,
+  /// the next proxy for round robin.
+  next: AtomicUsize,
+  /// the random state.
+  seed: AtomicU64,
+  /// the proxy used by host.
+  sticky: Mutex<HashMap<String, usize>>,
+}
+
+impl ProxyRotation {
+  /// set the proxy urls.
+  pub fn set_proxies(&self, proxies: Option<Vec<String>>) {
+    if let Ok(mut p) = self.proxies.write() {
+      *p = proxies.unwrap_or_default();
+    }
+  }
+
+  /// the proxy urls.
+  pub fn get_proxies(&self) -> Vec<String> {
+    match self.proxies.read() {
+      Ok(proxies) => proxies.clone(),
+      _ => Default::default(),
+    }
+  }
+
+  /// the rotation options with the defaults.
+  fn options(&self) -> NProxyRotation {
+    self.options.get().unwrap_or_default()
+  }
+
+  /// build a client for every proxy from the website configuration and reset the health.
+  pub fn prepare(&self, website: &spider::website::Website) {
+    let pool = self
+      .get_proxies()
+      .into_iter()
+      .map(|addr| {
+        let mut website = website.clone();
+        website.with_proxies(Some(vec![addr.clone()]));
+        // the crawl client handles the cache.
+        website.configuration.cache = false;
+
+        ProxyEntry {
+          client: website.configure_http_client(),
+          health: Default::default(),
+          addr,
+        }
+      })
+      .collect::<Vec<_>>();
+
+    if let Ok(mut p) = self.pool.write() {
+      *p = Arc::new(pool);
+    }
+    if let Ok(mut sticky) = self.sticky.lock() {
+      sticky.clear();
+    }
+
+    self.next.store(0, Ordering::Relaxed);
+    self.seed.store(
+      SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+        | 1,
+      Ordering::Relaxed,
+    );
+  }
+
+  /// stop rotating the proxies.
+  pub fn clear(&self) {
+    if let Ok(mut p) = self.pool.write() {
+      *p = Default::default();
+    }
+  }
+
+  /// the proxies for the crawl.
+  fn pool(&self) -> Arc<Vec<ProxyEntry>> {
+    match self.pool.read() {
+      Ok(pool) => pool.clone(),
+      _ => Default::default(),
+    }
+  }
+
+  /// a random number using xorshift.
+  fn random(&self) -> usize {
+    let mut x = self.seed.load(Ordering::Relaxed);
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    self.seed.store(x, Ordering::Relaxed);
+    x as usize
+  }
+
+  /// choose the proxy for the host skipping the proxies already tried.
+  fn select(&self, pool: &[ProxyEntry], host: &str, tried: &[usize]) -> Option<usize> {
+    let now = Instant::now();
+    let mut available = (0..pool.len())
+      .filter(|i| !tried.contains(i) && !pool[*i].is_ejected(now))
+      .collect::<Vec<usize>>();
+
+    // every proxy is ejected, keep going with the remaining ones.
+    if available.is_empty() {
+      available = (0..pool.len()).filter(|i| !tried.contains(i)).collect();
+    }
+
+    if available.is_empty() {
+      return None;
+    }
+
+    let index = match self.options().strategy.as_deref() {
+      Some("random") => available[self.random() % available.len()],
+      Some("least-failures") => *available
+        .iter()
+        .min_by_key(|i| pool[**i].failures())
+        .unwrap_or(&available[0]),
+      Some("sticky-per-host") => {
+        let mut sticky = self.sticky.lock().ok()?;
+
+        match sticky.get(host) {
+          Some(index) if available.contains(index) => *index,
+          _ => {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            host.hash(&mut hasher);
+            let index = available[hasher.finish() as usize % available.len()];
+            sticky.insert(host.to_string(), index);
+            index
+          }
+        }
+      }
+      _ => available[self.next.fetch_add(1, Ordering::Relaxed) % available.len()],
+    };
+
+    Some(index)
+  }
+
+  /// record the result of a request sent using the proxy.
+  fn record(&self, entry: &ProxyEntry, failed: bool, latency: Duration) {
+    let options = self.options();
+
+    if let Ok(mut health) = entry.health.lock() {
+      health.requests += 1;
+      health.latency += latency;
+
+      if failed {
+        health.failures += 1;
+        health.consecutive_failures += 1;
+
+        if health.consecutive_failures >= options.max_failures.unwrap_or(3).max(1) {
+          log::warn!("ejecting proxy {}", entry.addr);
+          health.consecutive_failures = 0;
+          health.ejections += 1;
+          health.ejected_until = Some(
+            Instant::now() + Duration::from_millis(options.eject_duration.unwrap_or(60000).into()),
+          );
+        }
+      } else {
+        health.consecutive_failures = 0;
+      }
+    }
+  }
+
+  /// the health of every proxy.
+  pub fn stats(&self) -> Vec<NProxyStats> {
+    let now = Instant::now();
+
+    self
+      .pool()
+      .iter()
+      .map(|entry| {
+        let ejected = entry.is_ejected(now);
+        let health = entry.health.lock();
+
+        match health {
+          Ok(health) => NProxyStats {
+            proxy: entry.addr.clone(),
+            requests: health.requests,
+            failures: health.failures,
+            ejections: health.ejections,
+            ejected,
+            average_latency: if health.requests > 0 {
+              health.latency.as_secs_f64() * 1000.0 / health.requests as f64
+            } else {
+              0.0
+            },
+          },
+          _ => NProxyStats {
+            proxy: entry.addr.clone(),
+            ..Default::default()
+          },
+        }
+      })
+      .collect()
+  }
+}
+
+/// is the response a failure caused by the proxy.
+fn is_proxy_failure(status: StatusCode) -> bool {
+  matches!(status.as_u16(), 407 | 429 | 502 | 503 | 504)
+}
+
+/// the client middleware sending the requests through the rotated proxies. The requests are sent by the proxy clients instead of the next middleware so it must be the last layer.
+pub struct ProxyMiddleware(pub Arc<ProxyRotation>);
+
+#[async_trait::async_trait]
+impl Middleware for ProxyMiddleware {
+  async fn handle(
+    &self,
+    req: Request,
+    extensions: &mut http::Extensions,
+    next: Next<'_>,
+  ) -> spider::reqwest_middleware::Result<Response> {
+    let pool = self.0.pool();
+
+    if pool.is_empty() {
+      return next.run(req, extensions).await;
+    }
+
+    let host = req.url().host_str().unwrap_or_default().to_string();
+    let mut tried = Vec::new();
+    let mut current = req;
+
+    loop {
+      let index = match self.0.select(&pool, &host, &tried) {
+        Some(index) => index,
+        _ => return next.run(current, extensions).await,
+      };
+
+      let entry = &pool[index];
+      // the request is sent again using another proxy if it fails.
+      let retry = current.try_clone();
+      tried.push(index);
+
+      let started = Instant::now();
+      let res = entry.client.execute(current).await;
+      let failed = match res {
+        Ok(ref res) => is_proxy_failure(res.status()),
+        _ => true,
+      };
+
+      self.0.record(entry, failed, started.elapsed());
+
+      match retry {
+        Some(retry) if failed && tried.len() < pool.len() => current = retry,
+        _ => return res,
+      }
+    }
+  }
+}
@@ -0,0 +1,195 @@
+use spider::compact_str::CompactString;
+use spider::packages::robotparser::parser::RobotFileParser;
+use spider::reqwest::StatusCode;
+
+/// the rules of a robots.txt group.
+#[napi(object)]
+#[derive(Default, Clone, Debug)]
+pub struct NRobotsGroup {
+  /// The user agents of the group.
+  pub user_agents: Vec<String>,
+  /// The allowed path patterns.
+  pub allow: Vec<String>,
+  /// The disallowed path patterns.
+  pub disallow: Vec<String>,
+  /// The delay between requests in seconds.
+  pub crawl_delay: Option<f64>,
+}
+
+/// the parsed robots.txt of a host.
+#[napi]
+#[derive(Default, Clone, Debug)]
+pub struct Robots {
+  /// the url of the robots.txt.
+  url: String,
+  /// the groups of rules by user agent.
+  groups: Vec<NRobotsGroup>,
+  /// the sitemaps listed.
+  sitemaps: Vec<String>,
+  /// the robots parser of the crawl. A missing file allows everything.
+  parser: Option<Box<RobotFileParser>>,
+  /// the user agent of the website used when none is passed.
+  user_agent: Option<String>,
+}
+
+#[napi]
+impl Robots {
+  #[napi(factory)]
+  /// parse the content of a robots.txt file.
+  pub fn parse(content: String, url: Option<String>) -> Self {
+    let mut robots = Robots {
+      url: url.unwrap_or_default(),
+      ..Default::default()
+    };
+    // the user agent lines in a row share the group.
+    let mut agents_open = false;
+
+    for line in content.lines() {
+      let line = line.split('#').next().unwrap_or_default();
+      let (key, value) = match line.split_once(':') {
+        Some((key, value)) => (key.trim().to_ascii_lowercase(), value.trim()),
+        _ => continue,
+      };
+
+      if key == "user-agent" {
+        if !agents_open {
+          robots.groups.push(NRobotsGroup::default());
+        }
+        agents_open = true;
+
+        if let Some(group) = robots.groups.last_mut() {
+          group.user_agents.push(value.to_string());
+        }
+        continue;
+      }
+
+      agents_open = false;
+
+      match (key.as_str(), robots.groups.last_mut()) {
+        ("sitemap", _) if !value.is_empty() => robots.sitemaps.push(value.to_string()),
+        ("allow", Some(group)) if !value.is_empty() => group.allow.push(value.to_string()),
+        ("disallow", Some(group)) if !value.is_empty() => group.disallow.push(value.to_string()),
+        ("crawl-delay", Some(group)) => group.crawl_delay = value.parse().ok(),
+        _ => (),
+      }
+    }
+
+    let mut parser = RobotFileParser::new();
+
+    parser.parse_str(&content);
+    parser.modified();
+    robots.parser = Some(parser);
+
+    robots
+  }
+
+  #[napi(getter)]
+  /// the url of the robots.txt.
+  pub fn url(&self) -> String {
+    self.url.clone()
+  }
+
+  #[napi(getter)]
+  /// the groups of rules by user agent.
+  pub fn groups(&self) -> Vec<NRobotsGroup> {
+    self.groups.clone()
+  }
+
+  #[napi(getter)]
+  /// the sitemaps listed.
+  pub fn sitemaps(&self) -> Vec<String> {
+    self.sitemaps.clone()
+  }
+
+  #[napi]
+  /// is the url or path allowed for the user agent with the rules the crawl respects. The rules are matched on the path and the query. The user agent of the website is used by default.
+  pub fn is_allowed(&self, url: String, user_agent: Option<String>) -> bool {
+    let path = match spider::url::Url::parse(&url) {
+      Ok(url) => match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        _ => url.path().to_string(),
+      },
+      _ if url.starts_with('/') => url,
+      _ => format!("/{url}"),
+    };
+
+    match self.parser {
+      Some(ref parser) => {
+        let user_agent = self.user_agent(user_agent);
+
+        // the parser drops the query of the path before matching the rules.
+        parser.can_fetch(&user_agent, &path)
+          && (!path.contains('?') || parser.allow_all || parser.entry_allowed(&user_agent, &path))
+      }
+      _ => true,
+    }
+  }
+
+  #[napi]
+  /// the delay between requests in seconds for the user agent. The user agent of the website is used by default.
+  pub fn crawl_delay(&self, user_agent: Option<String>) -> Option<f64> {
+    let user_agent = Some(Box::new(CompactString::from(self.user_agent(user_agent))));
+
+    self
+      .parser
+      .as_ref()?
+      .get_crawl_delay(&user_agent)
+      .map(|delay| delay.as_secs_f64())
+  }
+}
+
+impl Robots {
+  /// the user agent passed, else the user agent of the website or *.
+  fn user_agent(&self, user_agent: Option<String>) -> String {
+    user_agent
+      .or_else(|| self.user_agent.clone())
+      .unwrap_or_else(|| "*".into())
+  }
+
+  /// fetch the robots.txt of the website host for the user agent of the website. A missing file allows everything.
+  pub async fn fetch(
+    client: &spider::Client,
+    website_url: &str,
+    user_agent: Option<String>,
+  ) -> Self {
+    let url = match spider::url::Url::parse(website_url).and_then(|url| url.join("/robots.txt")) {
+      Ok(url) => url.to_string(),
+      _ => return Robots::default(),
+    };
+
+    let mut robots = match client.get(&url).send().await {
+      Ok(res) if res.status() == StatusCode::OK => match res.text().await {
+        Ok(content) => Robots::parse(content, None),
+        _ => Robots::default(),
+      },
+      // the crawl does not fetch any page when the robots.txt is forbidden.
+      Ok(res)
+        if matches!(
+          res.status(),
+          StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) =>
+      {
+        let mut parser = RobotFileParser::new();
+        parser.disallow_all = true;
+        parser.modified();
+
+        Robots {
+          parser: Some(parser),
+          ..Default::default()
+        }
+      }
+      Ok(res) => {
+        log::info!("{url} returned {}", res.status());
+        Robots::default()
+      }
+      Err(e) => {
+        log::warn!("{url} failed: {e}");
+        Robots::default()
+      }
+    };
+
+    robots.url = url;
+    robots.user_agent = user_agent;
+    robots
+  }
+}
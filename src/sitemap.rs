@@ -0,0 +1,165 @@
+use crate::robots::Robots;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sitemap::reader::{SiteMapEntity, SiteMapReader};
+use sitemap::structs::{ChangeFreq, LastMod, Location, Priority, UrlEntry};
+use std::collections::HashSet;
+use std::io::Read;
+
+/// the max amount of sitemap files fetched from the sitemap indexes.
+const MAX_SITEMAPS: usize = 1000;
+
+/// a url listed in a sitemap.
+#[napi(object)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NSitemapEntry {
+  /// The url of the page.
+  pub loc: String,
+  /// The date the page was last modified as RFC 3339.
+  pub lastmod: Option<String>,
+  /// How frequently the page is likely to change.
+  pub changefreq: Option<String>,
+  /// The priority of the page relative to the other pages of the site.
+  pub priority: Option<f64>,
+}
+
+/// the sitemaps of the website. The sitemap url set on the website is used first, then the sitemaps listed in robots.txt and /sitemap.xml as a fallback.
+pub async fn sitemap_urls(
+  client: &spider::Client,
+  website_url: &str,
+  sitemap_url: Option<&str>,
+) -> Vec<String> {
+  let base = match spider::url::Url::parse(website_url) {
+    Ok(url) => url,
+    _ => return sitemap_url.map(String::from).into_iter().collect(),
+  };
+
+  if let Some(sitemap_url) = sitemap_url {
+    return base
+      .join(sitemap_url)
+      .map(|url| vec![url.to_string()])
+      .unwrap_or_default();
+  }
+
+  let mut urls: Vec<String> = Robots::fetch(client, website_url, None)
+    .await
+    .sitemaps()
+    .iter()
+    .filter_map(|sitemap| base.join(sitemap).ok())
+    .map(|url| url.to_string())
+    .collect();
+
+  if urls.is_empty() {
+    if let Ok(url) = base.join("/sitemap.xml") {
+      urls.push(url.to_string());
+    }
+  }
+
+  urls
+}
+
+/// fetch the entries of the sitemaps following the sitemap indexes.
+pub async fn fetch_entries(client: &spider::Client, sitemaps: Vec<String>) -> Vec<NSitemapEntry> {
+  let mut entries: IndexMap<String, NSitemapEntry> = IndexMap::new();
+  let mut visited: HashSet<String> = HashSet::new();
+  let mut queue = sitemaps;
+
+  while let Some(sitemap) = queue.pop() {
+    if visited.len() >= MAX_SITEMAPS || !visited.insert(sitemap.clone()) {
+      continue;
+    }
+
+    let body = match fetch_body(client, &sitemap).await {
+      Some(body) => decompress(body),
+      _ => continue,
+    };
+
+    let mut sitemaps = Vec::new();
+
+    for entity in SiteMapReader::new(body.as_slice()) {
+      match entity {
+        SiteMapEntity::Url(entry) => {
+          if let Some(entry) = sitemap_entry(entry) {
+            entries.entry(entry.loc.clone()).or_insert(entry);
+          }
+        }
+        SiteMapEntity::SiteMap(entry) => {
+          if let Location::Url(url) = entry.loc {
+            sitemaps.push(url.to_string());
+          }
+        }
+        SiteMapEntity::Err(e) => log::info!("{sitemap} failed to parse: {e}"),
+      }
+    }
+
+    // keep the order of the index.
+    queue.extend(sitemaps.into_iter().rev());
+  }
+
+  entries.into_values().collect()
+}
+
+/// fetch the body of a successful response.
+pub(crate) async fn fetch_body(client: &spider::Client, url: &str) -> Option<Vec<u8>> {
+  match client.get(url).send().await {
+    Ok(res) if res.status().is_success() => res.bytes().await.ok().map(|b| b.to_vec()),
+    Ok(res) => {
+      log::info!("{url} returned {}", res.status());
+      None
+    }
+    Err(e) => {
+      log::warn!("{url} failed: {e}");
+      None
+    }
+  }
+}
+
+/// decompress the gzip sitemaps.
+fn decompress(body: Vec<u8>) -> Vec<u8> {
+  if !body.starts_with(&[0x1f, 0x8b]) {
+    return body;
+  }
+
+  let mut decoded = Vec::new();
+
+  match flate2::read::GzDecoder::new(body.as_slice()).read_to_end(&mut decoded) {
+    Ok(_) => decoded,
+    Err(e) => {
+      log::warn!("sitemap gzip failed: {e}");
+      body
+    }
+  }
+}
+
+/// convert the sitemap url entry.
+fn sitemap_entry(entry: UrlEntry) -> Option<NSitemapEntry> {
+  let loc = match entry.loc {
+    Location::Url(url) => url.to_string(),
+    _ => return None,
+  };
+
+  Some(NSitemapEntry {
+    loc,
+    lastmod: match entry.lastmod {
+      LastMod::DateTime(date) => Some(date.to_rfc3339()),
+      _ => None,
+    },
+    changefreq: match entry.changefreq {
+      ChangeFreq::Always => Some("always"),
+      ChangeFreq::Hourly => Some("hourly"),
+      ChangeFreq::Daily => Some("daily"),
+      ChangeFreq::Weekly => Some("weekly"),
+      ChangeFreq::Monthly => Some("monthly"),
+      ChangeFreq::Yearly => Some("yearly"),
+      ChangeFreq::Never => Some("never"),
+      _ => None,
+    }
+    .map(String::from),
+    priority: match entry.priority {
+      // keep the written decimals of the f32 priority.
+      Priority::Value(priority) => priority.to_string().parse().ok(),
+      _ => None,
+    },
+  })
+}
@@ -0,0 +1,263 @@
+use indexmap::IndexMap;
+use spider::compact_str::CompactString;
+use spider::configuration::Configuration;
+use spider::url::Url;
+use spider::CaseInsensitiveString;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// a url found during the crawl that was not crawled.
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct NSkippedUrl {
+  /// The url skipped.
+  pub url: String,
+  /// The rule that excluded the url.
+  #[napi(ts_type = "'external' | 'blacklist' | 'whitelist' | 'robots' | 'depth' | 'budget'")]
+  pub reason: String,
+  /// The page the url was found on.
+  pub found_on: Option<String>,
+}
+
+/// the links found during the crawl to report the urls skipped by the crawl rules.
+#[derive(Default)]
+pub struct SkipReport {
+  /// is the report enabled.
+  enabled: AtomicBool,
+  /// are the links recorded for the report or the page limit status.
+  recording: AtomicBool,
+  /// the links found with the page they were found on.
+  found: Mutex<IndexMap<String, String>>,
+  /// the urls blocked by robots.txt.
+  robots: Mutex<HashSet<String>>,
+}
+
+impl SkipReport {
+  /// enable or disable the report.
+  pub fn set_enabled(&self, enabled: bool) {
+    self.enabled.store(enabled, Ordering::Relaxed);
+  }
+
+  /// is the report enabled.
+  pub fn is_enabled(&self) -> bool {
+    self.enabled.load(Ordering::Relaxed)
+  }
+
+  /// clear the links of the last run. The links are also recorded for the page limit when set.
+  pub fn prepare(&self, limit: bool) {
+    self.clear();
+    self
+      .recording
+      .store(self.is_enabled() || limit, Ordering::Relaxed);
+  }
+
+  /// record a url blocked by robots.txt.
+  pub fn record_blocked(&self, url: String) {
+    if self.recording.load(Ordering::Relaxed) {
+      if let Ok(mut robots) = self.robots.lock() {
+        robots.insert(url);
+      }
+    }
+  }
+
+  /// clear the links found.
+  pub fn clear(&self) {
+    if let Ok(mut found) = self.found.lock() {
+      found.clear();
+    }
+    if let Ok(mut robots) = self.robots.lock() {
+      robots.clear();
+    }
+  }
+
+  /// record the links found on the page.
+  pub fn record(&self, page: &spider::page::Page) {
+    let links = match page.page_links {
+      Some(ref links) if self.recording.load(Ordering::Relaxed) => links,
+      _ => return,
+    };
+    let page_url = match Url::parse(page.get_url()) {
+      Ok(url) => url,
+      _ => return,
+    };
+
+    if let Ok(mut found) = self.found.lock() {
+      for link in links.iter() {
+        if let Ok(mut url) = page_url.join(link.as_ref()) {
+          if url.scheme() == "http" || url.scheme() == "https" {
+            url.set_fragment(None);
+            found
+              .entry(url.into())
+              .or_insert_with(|| page_url.to_string());
+          }
+        }
+      }
+    }
+  }
+
+  /// the links found that were not crawled with the rule that excluded them.
+  pub fn skipped(&self, website: &spider::website::Website) -> Vec<NSkippedUrl> {
+    if self.is_enabled() {
+      self.find_skipped(website)
+    } else {
+      Default::default()
+    }
+  }
+
+  /// were links found left by the crawl budget.
+  pub fn has_budget_skipped(&self, website: &spider::website::Website) -> bool {
+    self
+      .find_skipped(website)
+      .iter()
+      .any(|skipped| skipped.reason == "budget")
+  }
+
+  /// the links recorded that were not crawled with the rule that excluded them.
+  fn find_skipped(&self, website: &spider::website::Website) -> Vec<NSkippedUrl> {
+    let base = match website.get_url_parsed() {
+      Some(base) => base,
+      _ => return Default::default(),
+    };
+    let visited: HashSet<String> = website
+      .get_links()
+      .iter()
+      .map(|link| link.as_ref().trim_end_matches('/').to_string())
+      .collect();
+    let robots = match self.robots.lock() {
+      Ok(robots) => robots.clone(),
+      _ => Default::default(),
+    };
+    let exhausted = exhausted_budgets(&website.configuration, &visited);
+    let mut skipped: IndexMap<String, NSkippedUrl> = IndexMap::new();
+
+    if let Ok(found) = self.found.lock() {
+      for (link, found_on) in found.iter() {
+        if visited.contains(link.trim_end_matches('/')) {
+          continue;
+        }
+
+        let reason = Url::parse(link)
+          .ok()
+          .and_then(|url| skip_reason(&website.configuration, base, &url, &robots, &exhausted));
+
+        if let Some(reason) = reason {
+          skipped.insert(
+            link.clone(),
+            NSkippedUrl {
+              url: link.clone(),
+              reason: reason.into(),
+              found_on: Some(found_on.clone()),
+            },
+          );
+        }
+      }
+    }
+
+    // the robots.txt urls not found in the page links like the start url.
+    for url in robots {
+      skipped.entry(url.clone()).or_insert(NSkippedUrl {
+        url,
+        reason: "robots".into(),
+        found_on: None,
+      });
+    }
+
+    skipped.into_values().collect()
+  }
+}
+
+/// the first crawl rule excluding the url in the order spider applies them.
+fn skip_reason(
+  configuration: &Configuration,
+  base: &Url,
+  url: &Url,
+  robots: &HashSet<String>,
+  exhausted: &[String],
+) -> Option<&'static str> {
+  let link = CompactString::from(url.as_str());
+  let blacklist = configuration.get_blacklist_compiled();
+  let whitelist = configuration.get_whitelist_compiled();
+
+  if is_external(configuration, base, url) {
+    Some("external")
+  } else if !blacklist.is_empty() && spider::black_list::contains(blacklist, &link) {
+    Some("blacklist")
+  } else if !whitelist.is_empty() && !spider::black_list::contains(whitelist, &link) {
+    Some("whitelist")
+  } else if robots.contains(url.as_str()) {
+    Some("robots")
+  } else if configuration.depth_distance > 0
+    && url.path_segments().map_or(0, |segments| {
+      segments.filter(|segment| !segment.is_empty()).count()
+    }) > configuration.depth_distance
+  {
+    Some("depth")
+  } else if exhausted
+    .iter()
+    .any(|path| is_budget_path(path, url.path()))
+  {
+    Some("budget")
+  } else {
+    None
+  }
+}
+
+/// is the url outside of the domains allowed for the crawl.
+fn is_external(configuration: &Configuration, base: &Url, url: &Url) -> bool {
+  let (host, base_host) = match (url.host_str(), base.host_str()) {
+    (Some(host), Some(base_host)) => (host.to_ascii_lowercase(), base_host.to_ascii_lowercase()),
+    _ => return false,
+  };
+  let host = host.trim_start_matches("www.");
+  let domain = base_host.trim_start_matches("www.");
+  let name = |host: &str| host.split('.').next().unwrap_or_default().to_string();
+
+  let allowed = host == domain
+    || (configuration.subdomains && host.ends_with(&format!(".{domain}")))
+    || (configuration.tld && name(host) == name(domain))
+    || configuration
+      .external_domains_caseless
+      .contains(&CaseInsensitiveString::from(
+        url.host_str().unwrap_or_default(),
+      ))
+    || configuration
+      .external_domains_caseless
+      .contains(&CaseInsensitiveString::from("*"));
+
+  !allowed
+}
+
+/// the budget paths with every page used by the urls visited.
+fn exhausted_budgets(configuration: &Configuration, visited: &HashSet<String>) -> Vec<String> {
+  let budget = match configuration.budget {
+    Some(ref budget) => budget,
+    _ => return Default::default(),
+  };
+  let paths: Vec<String> = visited
+    .iter()
+    .filter_map(|link| Url::parse(link).ok())
+    .map(|url| url.path().to_string())
+    .collect();
+
+  budget
+    .iter()
+    .filter(|(path, amount)| {
+      let used = paths
+        .iter()
+        .filter(|url_path| is_budget_path(path.as_ref(), url_path))
+        .count();
+
+      used >= **amount as usize
+    })
+    .map(|(path, _)| path.to_string())
+    .collect()
+}
+
+/// does the budget path match the url path. The * path matches all of the paths.
+fn is_budget_path(path: &str, url_path: &str) -> bool {
+  path == "*"
+    || url_path
+      .trim_start_matches('/')
+      .starts_with(path.trim_start_matches('/'))
+}
@@ -0,0 +1,244 @@
+use crate::errors::NCrawlError;
+use crate::metrics::CrawlMetrics;
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// the request to record the pages queued for the stats listener answered once recorded.
+pub type FlushRequest = spider::tokio::sync::oneshot::Sender<()>;
+
+/// the live crawl statistics.
+#[napi(object)]
+#[derive(Default, Clone)]
+pub struct NCrawlStats {
+  /// The amount of pages fetched including failed pages.
+  pub pages_fetched: u32,
+  /// The amount of pages found that are not fetched yet.
+  pub pages_queued: u32,
+  /// The amount of pages that returned an error status code.
+  pub pages_failed: u32,
+  /// The amount of near-duplicate pages not sent. Requires the website.builder method website.with_dedupe to be set.
+  pub pages_duplicate: u32,
+  /// The amount of bytes downloaded.
+  pub bytes_downloaded: i64,
+  /// The amount of pages fetched per second.
+  pub requests_per_second: f64,
+  /// The amount of pages per HTTP status code.
+  pub status_codes: HashMap<String, u32>,
+  /// The time elapsed in milliseconds since the crawl started.
+  pub elapsed: f64,
+}
+
+/// the crawl statistics shared between the website and the running crawls.
+#[derive(Default)]
+pub struct CrawlStats {
+  /// the amount of pages fetched.
+  pages_fetched: AtomicU32,
+  /// the amount of pages failed.
+  pages_failed: AtomicU32,
+  /// the amount of near-duplicate pages.
+  pages_duplicate: AtomicU32,
+  /// the amount of bytes downloaded.
+  bytes_downloaded: AtomicI64,
+  /// the status code histogram.
+  status_codes: Mutex<HashMap<u16, u32>>,
+  /// the links found on the pages when `with_return_page_links` is set.
+  links_found: Mutex<HashSet<String>>,
+  /// the crawl start and end time.
+  timing: Mutex<(Option<Instant>, Option<Instant>)>,
+  /// is the crawl finished.
+  finished: AtomicBool,
+  /// the amount of retries configured for the crawl.
+  retry: AtomicU8,
+  /// the pages that failed by url.
+  errors: Mutex<IndexMap<String, NCrawlError>>,
+  /// the cumulative metrics across all runs.
+  pub metrics: CrawlMetrics,
+  /// the flush requests sent to the stats listener.
+  flush: Mutex<Option<spider::tokio::sync::mpsc::UnboundedSender<FlushRequest>>>,
+}
+
+impl CrawlStats {
+  /// reset the stats and start the timer.
+  pub fn start(&self, retry: u8) {
+    self.clear();
+    self.retry.store(retry, Ordering::Relaxed);
+    if let Ok(mut timing) = self.timing.lock() {
+      *timing = (Some(Instant::now()), None);
+    }
+  }
+
+  /// stop the timer.
+  pub fn finish(&self) {
+    if let Ok(mut timing) = self.timing.lock() {
+      timing.1 = Some(Instant::now());
+    }
+    self.finished.store(true, Ordering::Relaxed);
+  }
+
+  /// set the flush requests of a new stats listener.
+  pub fn set_flush(&self, flush: spider::tokio::sync::mpsc::UnboundedSender<FlushRequest>) {
+    if let Ok(mut f) = self.flush.lock() {
+      *f = Some(flush);
+    }
+  }
+
+  /// wait for the stats listener to record the pages sent before the call.
+  pub async fn flush(&self) {
+    let flush = match self.flush.lock() {
+      Ok(flush) => flush.clone(),
+      _ => None,
+    };
+
+    if let Some(flush) = flush {
+      let (tx, rx) = spider::tokio::sync::oneshot::channel();
+
+      if flush.send(tx).is_ok() {
+        let _ = rx.await;
+      }
+    }
+  }
+
+  /// is the crawl finished.
+  pub fn is_finished(&self) -> bool {
+    self.finished.load(Ordering::Relaxed)
+  }
+
+  /// reset all of the stats.
+  pub fn clear(&self) {
+    self.pages_fetched.store(0, Ordering::Relaxed);
+    self.pages_failed.store(0, Ordering::Relaxed);
+    self.pages_duplicate.store(0, Ordering::Relaxed);
+    self.bytes_downloaded.store(0, Ordering::Relaxed);
+    self.finished.store(false, Ordering::Relaxed);
+    if let Ok(mut status_codes) = self.status_codes.lock() {
+      status_codes.clear();
+    }
+    if let Ok(mut links_found) = self.links_found.lock() {
+      links_found.clear();
+    }
+    if let Ok(mut errors) = self.errors.lock() {
+      errors.clear();
+    }
+    if let Ok(mut timing) = self.timing.lock() {
+      *timing = (None, None);
+    }
+  }
+
+  /// record a page that was fetched.
+  pub fn record(&self, page: &spider::page::Page) {
+    let status_code = page.status_code;
+
+    self.metrics.record(page);
+    self.pages_fetched.fetch_add(1, Ordering::Relaxed);
+    self
+      .bytes_downloaded
+      .fetch_add(page.get_html_bytes_u8().len() as i64, Ordering::Relaxed);
+
+    if !status_code.is_success() && !status_code.is_redirection() {
+      self.pages_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    if let Ok(mut status_codes) = self.status_codes.lock() {
+      *status_codes.entry(status_code.as_u16()).or_default() += 1;
+    }
+
+    if let Some(error) = NCrawlError::new(page, self.retry.load(Ordering::Relaxed)) {
+      self.record_error(error);
+    }
+
+    if let Some(ref links) = page.page_links {
+      if let Ok(mut links_found) = self.links_found.lock() {
+        links_found.insert(page.get_url().to_string());
+        for link in links.iter() {
+          links_found.insert(link.as_ref().to_string());
+        }
+      }
+    }
+  }
+
+  /// record a url that failed adding up the attempts when the url failed before.
+  pub fn record_error(&self, error: NCrawlError) {
+    if let Ok(mut errors) = self.errors.lock() {
+      match errors.get_mut(&error.url) {
+        Some(prev) => {
+          let attempts = prev.attempts + error.attempts;
+          *prev = error;
+          prev.attempts = attempts;
+        }
+        _ => {
+          errors.insert(error.url.clone(), error);
+        }
+      }
+    }
+  }
+
+  /// record a near-duplicate page.
+  pub fn record_duplicate(&self) {
+    self.pages_duplicate.fetch_add(1, Ordering::Relaxed);
+  }
+
+  /// the amount of pages fetched.
+  pub fn pages_fetched(&self) -> u32 {
+    self.pages_fetched.load(Ordering::Relaxed)
+  }
+
+  /// get the pages that failed during the crawl.
+  pub fn errors(&self) -> Vec<NCrawlError> {
+    match self.errors.lock() {
+      Ok(errors) => errors.values().cloned().collect(),
+      _ => Default::default(),
+    }
+  }
+
+  /// the time elapsed since the crawl started.
+  pub fn elapsed(&self) -> Duration {
+    match self.timing.lock() {
+      Ok(timing) => match *timing {
+        (Some(start), Some(end)) => end.duration_since(start),
+        (Some(start), _) => start.elapsed(),
+        _ => Duration::ZERO,
+      },
+      _ => Duration::ZERO,
+    }
+  }
+
+  /// get a snapshot of the stats. The links visited is used to determine the queue when links are not returned on the pages.
+  pub fn snapshot(&self, links_visited: usize) -> NCrawlStats {
+    let pages_fetched = self.pages_fetched.load(Ordering::Relaxed);
+    let elapsed = self.elapsed();
+    let links_found = match self.links_found.lock() {
+      Ok(links_found) => links_found.len(),
+      _ => 0,
+    };
+
+    NCrawlStats {
+      pages_fetched,
+      pages_queued: if self.is_finished() {
+        0
+      } else {
+        links_visited
+          .max(links_found)
+          .saturating_sub(pages_fetched as usize) as u32
+      },
+      pages_failed: self.pages_failed.load(Ordering::Relaxed),
+      pages_duplicate: self.pages_duplicate.load(Ordering::Relaxed),
+      bytes_downloaded: self.bytes_downloaded.load(Ordering::Relaxed),
+      requests_per_second: if elapsed.is_zero() {
+        0.0
+      } else {
+        pages_fetched as f64 / elapsed.as_secs_f64()
+      },
+      status_codes: match self.status_codes.lock() {
+        Ok(status_codes) => status_codes
+          .iter()
+          .map(|(code, count)| (code.to_string(), *count))
+          .collect(),
+        _ => Default::default(),
+      },
+      elapsed: elapsed.as_secs_f64() * 1000.0,
+    }
+  }
+}
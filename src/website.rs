@@ -1,12 +1,40 @@
+use crate::auth::NAuth;
+use crate::automation::{to_automation_scripts, NAutomationStep};
+use crate::blocking::NResourceBlocking;
+use crate::browser::ChromeSettings;
+use crate::changes::NChangeTracking;
+use crate::client::{ClientLayers, CronJob};
+use crate::config::{
+  to_header_map, NChromeIntercept, NCron, NWaitFor, NWaitForSelector, NWebsiteConfig,
+};
 use crate::conversions::{object_to_u8, ObjectConvert};
+use crate::cookies::{CookieStore, NCookie};
+use crate::crawl_pool::{NSiteResult, RequestLimits};
+use crate::dedupe::{NDedupe, NDuplicatePage};
+use crate::device::{device_names, device_preset, NViewport};
+use crate::errors::NCrawlError;
+use crate::evaluate::to_execution_scripts;
+use crate::hooks::PageHooks;
+use crate::login::{Login, NLoginConfig};
+use crate::normalize::NUrlNormalization;
+use crate::npage::header_map_to_hash_map;
+use crate::pdf::{NPdfOptions, PdfRenderer};
+use crate::pool::{BrowserLease, BrowserPool, Pool};
+use crate::proxy::{NProxyRotation, NProxyStats};
+use crate::robots::Robots;
+use crate::sitemap::{fetch_entries, sitemap_urls, NSitemapEntry};
+use crate::skipped::{NSkippedUrl, SkipReport};
+use crate::stats::{CrawlStats, FlushRequest, NCrawlStats};
 use crate::{NPage, BUFFER};
 use indexmap::IndexMap;
 use napi::{bindgen_prelude::Object, tokio::task::JoinHandle};
 use napi::{Env, JsUnknown};
 use spider::compact_str::CompactString;
 use spider::configuration::{WaitForDelay, WaitForIdleNetwork, WaitForSelector};
+use spider::spider_fingerprint::configs::Tier;
 use spider::{configuration::RedirectPolicy, utils::shutdown};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 #[napi]
 /// a website holding the inner spider::website::Website from Rust fit for nodejs.
@@ -20,13 +48,111 @@ pub struct Website {
   /// do not convert content to UT8.
   raw_content: bool,
   /// the data collected.
-  collected_data: Box<Vec<Vec<u8>>>,
+  collected_data: Vec<Vec<u8>>,
   /// is the crawl running in the background.
   running_in_background: bool, // /// the file handle for storing data
-                               // file_handle: Option<spider::tokio::fs::File>,
+  // file_handle: Option<spider::tokio::fs::File>,
+  /// the crawl wide timeout and page limit.
+  crawl_limits: CrawlLimits,
+  /// the live crawl statistics.
+  crawl_stats: Arc<CrawlStats>,
+  /// the cookies imported and received during the crawl.
+  cookies: Arc<CookieStore>,
+  /// the login step to run before the crawl.
+  login: Arc<Login>,
+  /// the auth and proxy middleware for the HTTP client.
+  client_layers: ClientLayers,
+  /// the steps run on every page before it is sent to javascript.
+  page_hooks: Arc<PageHooks>,
+  /// the links found to report the urls skipped by the crawl rules.
+  skip_report: Arc<SkipReport>,
+  /// the chrome instances shared with other websites.
+  browser_pool: Option<Arc<Pool>>,
+  /// the chrome settings replaced for the current crawl by the browser pool or the PDF renders.
+  chrome_settings: Option<ChromeSettings>,
+  /// the automation steps by url path.
+  automation_scripts: Option<std::collections::HashMap<String, Vec<NAutomationStep>>>,
+  /// the javascript expressions to evaluate by url path.
+  evaluate_scripts: Option<std::collections::HashMap<String, String>>,
+  /// the sitemap url.
+  sitemap_url: Option<String>,
+  /// the subscription handle collecting the statistics.
+  stats_handle: Option<JoinHandle<()>>,
+  /// the handle sending the progress of the current run.
+  progress_handle: Option<JoinHandle<()>>,
+  /// the metrics server handle.
+  metrics_handle: Option<JoinHandle<()>>,
+  /// the progress callback and the interval to send the statistics.
+  on_progress: Option<(
+    napi::threadsafe_function::ThreadsafeFunction<NCrawlStats>,
+    Duration,
+  )>,
+}
+
+/// the crawl wide limits that end a run early.
+#[derive(Clone, Default)]
+struct CrawlLimits {
+  /// the max wall-clock duration of the crawl.
+  timeout: Option<Duration>,
+  /// the max amount of pages to crawl. If the value is 0 there is no limit.
+  limit: u32,
+  /// the crawl budget set without the page limit.
+  budget: Option<std::collections::HashMap<String, u32>>,
+  /// the links found to tell if pages were left when the limit was hit.
+  links: Arc<SkipReport>,
+  /// the status set when the last crawl ended from a limit.
+  status: Arc<Mutex<Option<&'static str>>>,
+}
+
+impl CrawlLimits {
+  /// reset the limit status before a new run.
+  fn reset(&self) {
+    if let Ok(mut status) = self.status.lock() {
+      status.take();
+    }
+  }
+
+  /// get the status set when the last crawl ended from a limit.
+  fn status(&self) -> Option<&'static str> {
+    match self.status.lock() {
+      Ok(status) => *status,
+      _ => None,
+    }
+  }
+
+  /// mark the status when the crawl ended from the timeout or page limit. The limit is reached when links were left by the budget of the limit.
+  fn finish(&self, website: &spider::website::Website, started: Instant) {
+    let limit_reached = self.limit > 0
+      && website.size() >= self.limit as usize
+      && self.links.has_budget_skipped(website);
+    let status = match self.timeout {
+      Some(timeout) if started.elapsed() >= timeout => Some("TimedOut"),
+      _ if limit_reached => Some("LimitReached"),
+      _ => None,
+    };
+
+    if let Ok(mut s) = self.status.lock() {
+      *s = status;
+    }
+  }
+}
+
+impl Drop for Website {
+  fn drop(&mut self) {
+    let handles = [
+      self.stats_handle.take(),
+      self.progress_handle.take(),
+      self.metrics_handle.take(),
+    ];
+
+    for handle in handles.into_iter().flatten() {
+      handle.abort();
+    }
+  }
 }
 
 #[napi(object)]
+#[allow(dead_code)]
 struct PageEvent {
   pub page: NPage,
 }
@@ -36,21 +162,217 @@ impl Website {
   #[napi(constructor)]
   /// a new website.
   pub fn new(url: String, raw_content: Option<bool>) -> Self {
+    let skip_report = Arc::new(SkipReport::default());
+
     Website {
       inner: spider::website::Website::new(&url),
       subscription_handles: IndexMap::new(),
       crawl_handles: IndexMap::new(),
       raw_content: raw_content.unwrap_or_default(),
-      collected_data: Box::new(Vec::new()),
+      collected_data: Vec::new(),
       running_in_background: false, // file_handle: None,
+      crawl_limits: CrawlLimits {
+        links: skip_report.clone(),
+        ..Default::default()
+      },
+      crawl_stats: Arc::new(CrawlStats::default()),
+      cookies: Arc::new(CookieStore::default()),
+      login: Arc::new(Login::default()),
+      client_layers: ClientLayers::default(),
+      page_hooks: Arc::new(PageHooks::default()),
+      skip_report,
+      browser_pool: None,
+      chrome_settings: None,
+      automation_scripts: None,
+      evaluate_scripts: None,
+      sitemap_url: None,
+      stats_handle: None,
+      progress_handle: None,
+      metrics_handle: None,
+      on_progress: None,
+    }
+  }
+
+  #[napi(factory)]
+  /// a new website from a config object with the builder options by method name. Unknown keys are errors.
+  pub fn from_config(
+    #[napi(ts_arg_type = "NWebsiteConfig")] config: serde_json::Value,
+  ) -> napi::Result<Self> {
+    let config = NWebsiteConfig::from_value(config).map_err(napi::Error::from_reason)?;
+    Website::from_website_config(config)
+  }
+
+  #[napi(factory)]
+  /// a new website from a JSON or YAML config file with the builder options by method name. Unknown keys are errors.
+  pub fn from_config_file(path: String) -> napi::Result<Self> {
+    let config = NWebsiteConfig::from_file(&path).map_err(napi::Error::from_reason)?;
+    Website::from_website_config(config)
+  }
+
+  #[napi]
+  /// get the effective configuration including the defaults. The result can be passed to `Website.fromConfig`. The device preset shows as the viewport and user agent. The passwords, login form values, cookie values, OpenAI api keys, credential headers and proxy passwords are redacted.
+  pub fn get_config(&self) -> NWebsiteConfig {
+    self.website_config().redacted()
+  }
+
+  /// the effective configuration with the secrets.
+  fn website_config(&self) -> NWebsiteConfig {
+    let configuration = &self.inner.configuration;
+    let duration_ms = |duration: Option<Duration>| duration.map(|d| d.as_millis() as u32);
+    let wait_for = |timeout: Option<Duration>| NWaitFor {
+      seconds: timeout.map(|d| d.as_secs() as u32),
+      nanos: timeout.map(|d| d.subsec_nanos()),
+    };
+    let wait = configuration.wait_for.as_ref();
+    let cookies = self.cookies.get_all();
+    let chrome_intercept = self.page_hooks.blocking.chrome_intercept(&self.inner);
+
+    NWebsiteConfig {
+      url: self.inner.get_url().to_string(),
+      raw_content: Some(self.raw_content),
+      headers: configuration
+        .headers
+        .as_ref()
+        .map(|headers| header_map_to_hash_map(&headers.0)),
+      cookies: if cookies.is_empty() {
+        None
+      } else {
+        serde_json::to_value(cookies).ok()
+      },
+      login: self.login.config.get(),
+      auth: self.client_layers.auth.get_credentials(),
+      user_agent: configuration.user_agent.as_ref().map(|ua| ua.to_string()),
+      respect_robots_txt: Some(configuration.respect_robots_txt),
+      full_resources: Some(configuration.full_resources),
+      chrome_intercept: Some(NChromeIntercept {
+        enabled: chrome_intercept.enabled,
+        block_images: Some(chrome_intercept.block_visuals),
+      }),
+      resource_blocking: self.page_hooks.blocking.options.get(),
+      url_normalization: self.page_hooks.normalizer.options.get(),
+      dedupe: self.page_hooks.dedupe.options.get(),
+      change_tracking: self.page_hooks.changes.options.get(),
+      chrome_connection: match self.chrome_settings {
+        Some(ref settings) => settings.chrome_connection_url.clone(),
+        _ => configuration.chrome_connection_url.clone(),
+      },
+      preserve_host_header: Some(configuration.preserve_host_header),
+      subdomains: Some(configuration.subdomains),
+      tld: Some(configuration.tld),
+      http2_prior_knowledge: Some(configuration.http2_prior_knowledge),
+      request_timeout: duration_ms(configuration.request_timeout),
+      crawl_timeout: duration_ms(self.crawl_limits.timeout),
+      limit: Some(self.crawl_limits.limit),
+      external_domains: Some(
+        configuration
+          .external_domains_caseless
+          .iter()
+          .map(|domain| domain.to_string())
+          .collect(),
+      ),
+      device: None,
+      viewport: configuration.viewport.map(Into::into),
+      timezone: configuration.timezone_id.as_deref().cloned(),
+      locale: configuration.locale.as_deref().cloned(),
+      stealth: match configuration.stealth_mode {
+        Tier::Basic => Some(true),
+        Tier::None => Some(false),
+        _ => None,
+      },
+      danger_accept_invalid_certs: Some(configuration.accept_invalid_certs),
+      budget: self.crawl_limits.budget.clone(),
+      redirect_limit: Some(configuration.redirect_limit as u32),
+      redirect_policy: Some(
+        match configuration.redirect_policy {
+          RedirectPolicy::Strict => "strict",
+          _ => "loose",
+        }
+        .into(),
+      ),
+      blacklist_url: configuration
+        .blacklist_url
+        .as_ref()
+        .map(|urls| urls.iter().map(|url| url.to_string()).collect()),
+      whitelist_url: configuration
+        .whitelist_url
+        .as_ref()
+        .map(|urls| urls.iter().map(|url| url.to_string()).collect()),
+      wait_for_delay: wait
+        .and_then(|wait| wait.delay.as_ref())
+        .map(|delay| wait_for(delay.timeout)),
+      wait_for_selector: wait
+        .and_then(|wait| wait.selector.as_ref())
+        .map(|selector| {
+          let timeout = wait_for(selector.timeout);
+          NWaitForSelector {
+            selector: selector.selector.clone(),
+            seconds: timeout.seconds,
+            nanos: timeout.nanos,
+          }
+        }),
+      wait_for_idle_network: wait
+        .and_then(|wait| wait.idle_network.as_ref())
+        .map(|idle_network| wait_for(idle_network.timeout)),
+      automation_scripts: self.automation_scripts.clone(),
+      evaluate_scripts: self.evaluate_scripts.clone(),
+      cron: if configuration.cron_str.is_empty() {
+        None
+      } else {
+        Some(NCron {
+          expression: configuration.cron_str.clone(),
+          cron_type: Some(
+            match configuration.cron_type {
+              spider::website::CronType::Scrape => "scrape",
+              _ => "crawl",
+            }
+            .into(),
+          ),
+        })
+      },
+      openai: configuration
+        .openai_config
+        .as_ref()
+        .and_then(|configs| serde_json::to_value(configs).ok()),
+      screenshot: configuration
+        .screenshot
+        .as_ref()
+        .and_then(|configs| serde_json::to_value(configs).ok()),
+      pdf: self.page_hooks.pdf.options.get(),
+      delay: Some(configuration.delay as u32),
+      depth: Some(configuration.depth as u32),
+      retry: Some(configuration.retry.into()),
+      return_page_links: Some(self.page_hooks.return_page_links()),
+      skip_report: Some(self.skip_report.is_enabled()),
+      caching: Some(configuration.cache),
+      sitemap: self.sitemap_url.clone(),
+      proxies: Some(self.client_layers.proxies.get_proxies()),
+      proxy_rotation: self.client_layers.proxies.options.get(),
     }
   }
 
-  /// Get the crawl status.
+  #[napi(js_name = "clone")]
+  /// create an independent copy of the website with the same configuration and callbacks. The crawl state, links and data are not copied.
+  pub fn clone_website(&self) -> napi::Result<Website> {
+    let mut website = Website::from_website_config(self.website_config())?;
+
+    website
+      .client_layers
+      .auth_provider
+      .set_callback(self.client_layers.auth_provider.get_callback());
+    website.browser_pool = self.browser_pool.clone();
+    website.on_progress = self.on_progress.clone();
+
+    Ok(website)
+  }
+
+  /// Get the crawl status. This is `TimedOut` or `LimitReached` when the crawl ended from `website.withCrawlTimeout` or `website.withLimit`.
   #[napi(getter)]
   pub fn status(&self) -> String {
     use std::string::ToString;
-    self.inner.get_status().to_string()
+    match self.crawl_limits.status() {
+      Some(status) => status.to_string(),
+      _ => self.inner.get_status().to_string(),
+    }
   }
 
   #[napi]
@@ -62,14 +384,13 @@ impl Website {
           .collected_data
           .push(object_to_u8(ObjectConvert::Val(deserialized))?);
       }
-      _ => match obj.coerce_to_object() {
-        Ok(obj) => {
+      _ => {
+        if let Ok(obj) = obj.coerce_to_object() {
           self
             .collected_data
             .push(object_to_u8(ObjectConvert::Obj(obj))?);
         }
-        _ => (),
-      },
+      }
     }
 
     Ok(())
@@ -100,7 +421,6 @@ impl Website {
       Some(p) => {
         let base_dir = p
           .split("/")
-          .into_iter()
           .map(|f| {
             if f.contains(".") {
               "".to_string()
@@ -137,7 +457,7 @@ impl Website {
         file.write_all(b"\n").await?;
       }
       // transform data step needed to auto convert type ..
-      file.write_all(&data).await?;
+      file.write_all(data).await?;
     }
 
     Ok(())
@@ -149,18 +469,13 @@ impl Website {
     &mut self,
     on_page_event: napi::threadsafe_function::ThreadsafeFunction<NPage>,
   ) -> u32 {
-    let mut rx2 = self
-      .inner
-      .subscribe(*BUFFER / 2)
-      .expect("sync feature should be enabled");
+    let mut rx2 = self.inner.subscribe(*BUFFER / 2);
     let raw_content = self.raw_content;
+    let page_hooks = self.page_hooks.clone();
 
     let handle = spider::tokio::spawn(async move {
       while let Ok(res) = rx2.recv().await {
-        on_page_event.call(
-          Ok(NPage::new(&res, raw_content)),
-          napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
-        );
+        page_hooks.send(&on_page_event, &res, raw_content).await;
       }
     });
 
@@ -244,11 +559,19 @@ impl Website {
     on_page_event: Option<napi::threadsafe_function::ThreadsafeFunction<NPage>>,
     background: Option<bool>,
     headless: Option<bool>,
-  ) {
+  ) -> napi::Result<()> {
     // only run in background if on_page_event is handled for streaming.
     let background = background.is_some() && background.unwrap_or_default();
     let headless = headless.is_some() && headless.unwrap_or_default();
     let raw_content = self.raw_content;
+    let page_hooks = self.page_hooks.clone();
+
+    let browser_lease = self.setup_crawl(on_page_event.as_ref(), headless).await?;
+
+    let crawl_limits = self.crawl_limits.clone();
+    let crawl_stats = self.crawl_stats.clone();
+    let changes = self.page_hooks.changes.clone();
+    let pdf = self.page_hooks.pdf.clone();
 
     if background {
       self.running_in_background = background;
@@ -258,16 +581,11 @@ impl Website {
       Some(callback) => {
         if background {
           let mut website = self.inner.clone();
-          let mut rx2 = website
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
+          let mut rx2 = website.subscribe(*BUFFER / 2);
 
           let handle = spider::tokio::spawn(async move {
             while let Ok(res) = rx2.recv().await {
-              callback.call(
-                Ok(NPage::new(&res, raw_content)),
-                napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
-              );
+              page_hooks.send(&callback, &res, raw_content).await;
             }
           });
 
@@ -277,11 +595,18 @@ impl Website {
           };
 
           let crawl_handle = spider::tokio::spawn(async move {
+            let started = Instant::now();
             if headless {
               website.crawl().await;
             } else {
               website.crawl_raw().await;
             }
+            crawl_limits.finish(&website, started);
+            crawl_stats.flush().await;
+            changes.finish(&website);
+            crawl_stats.finish();
+            pdf.finish().await;
+            drop(browser_lease);
           });
 
           let id = match self.subscription_handles.last() {
@@ -292,25 +617,24 @@ impl Website {
           self.crawl_handles.insert(crawl_id, crawl_handle);
           self.subscription_handles.insert(id, handle);
         } else {
-          let mut rx2 = self
-            .inner
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
+          let mut rx2 = self.inner.subscribe(*BUFFER / 2);
 
           let handle = spider::tokio::spawn(async move {
             while let Ok(res) = rx2.recv().await {
-              callback.call(
-                Ok(NPage::new(&res, raw_content)),
-                napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
-              );
+              page_hooks.send(&callback, &res, raw_content).await;
             }
           });
 
+          let started = Instant::now();
           if headless {
             self.inner.crawl().await;
           } else {
             self.inner.crawl_raw().await;
           }
+          crawl_limits.finish(&self.inner, started);
+          crawl_stats.flush().await;
+          changes.finish(&self.inner);
+          crawl_stats.finish();
 
           let id = match self.subscription_handles.last() {
             Some(handle) => handle.0 + 1,
@@ -330,23 +654,134 @@ impl Website {
           };
 
           let crawl_handle = spider::tokio::spawn(async move {
+            let started = Instant::now();
             if headless {
               website.crawl().await;
             } else {
               website.crawl_raw().await;
             }
+            crawl_limits.finish(&website, started);
+            crawl_stats.flush().await;
+            changes.finish(&website);
+            crawl_stats.finish();
+            pdf.finish().await;
+            drop(browser_lease);
           });
 
           self.crawl_handles.insert(crawl_id, crawl_handle);
         } else {
+          let started = Instant::now();
           if headless {
             self.inner.crawl().await;
           } else {
             self.inner.crawl_raw().await;
           }
+          crawl_limits.finish(&self.inner, started);
+          crawl_stats.flush().await;
+          changes.finish(&self.inner);
+          crawl_stats.finish();
         }
       }
     }
+
+    if !background {
+      // wait for the pages rendered as PDF.
+      self.page_hooks.pdf.finish().await;
+    }
+    self.restore_chrome_settings();
+
+    Ok(())
+  }
+
+  #[napi]
+  /// Crawl only the urls listed in the sitemaps including the sitemap indexes, the gzip sitemaps and the sitemaps listed in robots.txt. The links found on the pages are not followed.
+  pub async unsafe fn crawl_sitemap(
+    &mut self,
+    on_page_event: Option<napi::threadsafe_function::ThreadsafeFunction<NPage>>,
+    headless: Option<bool>,
+  ) -> napi::Result<()> {
+    let headless = headless.is_some() && headless.unwrap_or_default();
+    let raw_content = self.raw_content;
+    let page_hooks = self.page_hooks.clone();
+
+    let _browser_lease = self.setup_crawl(on_page_event.as_ref(), headless).await?;
+
+    let client = self.http_client();
+    let sitemaps = sitemap_urls(
+      &client,
+      self.inner.get_url().as_ref(),
+      self.sitemap_url.as_deref(),
+    )
+    .await;
+    let links: spider::hashbrown::HashSet<spider::CaseInsensitiveString> =
+      fetch_entries(&client, sitemaps)
+        .await
+        .into_iter()
+        .map(|entry| entry.loc.into())
+        .collect();
+
+    let handle = on_page_event.map(|callback| {
+      let mut rx2 = self.inner.subscribe(*BUFFER / 2);
+
+      spider::tokio::spawn(async move {
+        while let Ok(res) = rx2.recv().await {
+          page_hooks.send(&callback, &res, raw_content).await;
+        }
+      })
+    });
+
+    // the urls listed are the start links of a copy sharing the subscriptions. The links found on the pages are not followed
+    // and the copy keeps the start page skipped by spider for the persisted links away from the next crawls.
+    let mut website = self.inner.clone();
+
+    website.clear_all().await;
+    website.set_extra_links(links);
+    website.persist_links();
+    website.with_on_should_crawl_callback(Some(|_: &spider::page::Page| false));
+    website.with_ignore_sitemap(true);
+
+    // the skipped start page still takes a page of the `*` budget.
+    {
+      let mut budget = limit_budget(&self.crawl_limits);
+
+      if let Some(all) = budget.get_mut("*") {
+        *all += 1;
+        website.with_budget(Some(budget));
+      }
+    }
+
+    let started = Instant::now();
+    if website.get_extra_links().is_empty() {
+      log::info!("{} has no sitemap urls to crawl", website.get_url());
+    } else if headless {
+      website.crawl().await;
+    } else {
+      website.crawl_raw().await;
+    }
+
+    self.inner.clear_all().await;
+    for link in website.get_links() {
+      self.inner.insert_link(&link).await;
+    }
+    self.inner.set_status(*website.get_status());
+    self.crawl_limits.finish(&self.inner, started);
+    self.crawl_stats.flush().await;
+    self.page_hooks.changes.finish(&self.inner);
+    self.crawl_stats.finish();
+
+    if let Some(handle) = handle {
+      let id = match self.subscription_handles.last() {
+        Some(handle) => handle.0 + 1,
+        _ => 0,
+      };
+
+      self.subscription_handles.insert(id, handle);
+    }
+
+    self.page_hooks.pdf.finish().await;
+    self.restore_chrome_settings();
+
+    Ok(())
   }
 
   #[napi]
@@ -355,10 +790,18 @@ impl Website {
     &mut self,
     on_page_event: Option<napi::threadsafe_function::ThreadsafeFunction<NPage>>,
     background: Option<bool>,
-  ) {
+  ) -> napi::Result<()> {
     // only run in background if on_page_event is handled for streaming.
     let background = background.is_some() && background.unwrap_or_default();
     let raw_content = self.raw_content;
+    let page_hooks = self.page_hooks.clone();
+
+    let browser_lease = self.setup_crawl(on_page_event.as_ref(), true).await?;
+
+    let crawl_limits = self.crawl_limits.clone();
+    let crawl_stats = self.crawl_stats.clone();
+    let changes = self.page_hooks.changes.clone();
+    let pdf = self.page_hooks.pdf.clone();
 
     if background {
       self.running_in_background = background;
@@ -368,16 +811,11 @@ impl Website {
       Some(callback) => {
         if background {
           let mut website = self.inner.clone();
-          let mut rx2 = website
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
+          let mut rx2 = website.subscribe(*BUFFER / 2);
 
           let handle = spider::tokio::spawn(async move {
             while let Ok(res) = rx2.recv().await {
-              callback.call(
-                Ok(NPage::new(&res, raw_content)),
-                napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
-              );
+              page_hooks.send(&callback, &res, raw_content).await;
             }
           });
 
@@ -387,7 +825,14 @@ impl Website {
           };
 
           let crawl_handle = spider::tokio::spawn(async move {
+            let started = Instant::now();
             website.crawl_smart().await;
+            crawl_limits.finish(&website, started);
+            crawl_stats.flush().await;
+            changes.finish(&website);
+            crawl_stats.finish();
+            pdf.finish().await;
+            drop(browser_lease);
           });
 
           let id = match self.subscription_handles.last() {
@@ -398,21 +843,20 @@ impl Website {
           self.crawl_handles.insert(crawl_id, crawl_handle);
           self.subscription_handles.insert(id, handle);
         } else {
-          let mut rx2 = self
-            .inner
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
+          let mut rx2 = self.inner.subscribe(*BUFFER / 2);
 
           let handle = spider::tokio::spawn(async move {
             while let Ok(res) = rx2.recv().await {
-              callback.call(
-                Ok(NPage::new(&res, raw_content)),
-                napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
-              );
+              page_hooks.send(&callback, &res, raw_content).await;
             }
           });
 
+          let started = Instant::now();
           self.inner.crawl_smart().await;
+          crawl_limits.finish(&self.inner, started);
+          crawl_stats.flush().await;
+          changes.finish(&self.inner);
+          crawl_stats.finish();
           let _ = handle.await;
         }
       }
@@ -426,15 +870,35 @@ impl Website {
           };
 
           let crawl_handle = spider::tokio::spawn(async move {
+            let started = Instant::now();
             website.crawl_smart().await;
+            crawl_limits.finish(&website, started);
+            crawl_stats.flush().await;
+            changes.finish(&website);
+            crawl_stats.finish();
+            pdf.finish().await;
+            drop(browser_lease);
           });
 
           self.crawl_handles.insert(crawl_id, crawl_handle);
         } else {
+          let started = Instant::now();
           self.inner.crawl_smart().await;
+          crawl_limits.finish(&self.inner, started);
+          crawl_stats.flush().await;
+          changes.finish(&self.inner);
+          crawl_stats.finish();
         }
       }
     }
+
+    if !background {
+      // wait for the pages rendered as PDF.
+      self.page_hooks.pdf.finish().await;
+    }
+    self.restore_chrome_settings();
+
+    Ok(())
   }
 
   #[napi]
@@ -444,11 +908,19 @@ impl Website {
     on_page_event: Option<napi::threadsafe_function::ThreadsafeFunction<NPage>>,
     background: Option<bool>,
     headless: Option<bool>,
-  ) {
+  ) -> napi::Result<()> {
     let headless = headless.is_some() && headless.unwrap_or_default();
     let raw_content = self.raw_content;
+    let page_hooks = self.page_hooks.clone();
     let background = background.is_some() && background.unwrap_or_default();
 
+    let browser_lease = self.setup_crawl(on_page_event.as_ref(), headless).await?;
+
+    let crawl_limits = self.crawl_limits.clone();
+    let crawl_stats = self.crawl_stats.clone();
+    let changes = self.page_hooks.changes.clone();
+    let pdf = self.page_hooks.pdf.clone();
+
     if background {
       self.running_in_background = background;
     }
@@ -457,16 +929,11 @@ impl Website {
       Some(callback) => {
         if background {
           let mut website = self.inner.clone();
-          let mut rx2 = website
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
+          let mut rx2 = website.subscribe(*BUFFER / 2);
 
           let handle = spider::tokio::spawn(async move {
             while let Ok(res) = rx2.recv().await {
-              callback.call(
-                Ok(NPage::new(&res, raw_content)),
-                napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
-              );
+              page_hooks.send(&callback, &res, raw_content).await;
             }
           });
 
@@ -476,11 +943,18 @@ impl Website {
           };
 
           let crawl_handle = spider::tokio::spawn(async move {
+            let started = Instant::now();
             if headless {
               website.scrape().await;
             } else {
               website.scrape_raw().await;
             }
+            crawl_limits.finish(&website, started);
+            crawl_stats.flush().await;
+            changes.finish(&website);
+            crawl_stats.finish();
+            pdf.finish().await;
+            drop(browser_lease);
           });
 
           let id = match self.subscription_handles.last() {
@@ -491,25 +965,24 @@ impl Website {
           self.crawl_handles.insert(crawl_id, crawl_handle);
           self.subscription_handles.insert(id, handle);
         } else {
-          let mut rx2 = self
-            .inner
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
+          let mut rx2 = self.inner.subscribe(*BUFFER / 2);
 
           let handle = spider::tokio::spawn(async move {
             while let Ok(res) = rx2.recv().await {
-              callback.call(
-                Ok(NPage::new(&res, raw_content)),
-                napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
-              );
+              page_hooks.send(&callback, &res, raw_content).await;
             }
           });
 
+          let started = Instant::now();
           if headless {
             self.inner.scrape().await;
           } else {
             self.inner.scrape_raw().await;
           }
+          crawl_limits.finish(&self.inner, started);
+          crawl_stats.flush().await;
+          changes.finish(&self.inner);
+          crawl_stats.finish();
 
           let _ = handle.await;
         }
@@ -524,23 +997,43 @@ impl Website {
           };
 
           let crawl_handle = spider::tokio::spawn(async move {
+            let started = Instant::now();
             if headless {
               website.scrape().await;
             } else {
               website.scrape_raw().await;
             }
+            crawl_limits.finish(&website, started);
+            crawl_stats.flush().await;
+            changes.finish(&website);
+            crawl_stats.finish();
+            pdf.finish().await;
+            drop(browser_lease);
           });
 
           self.crawl_handles.insert(crawl_id, crawl_handle);
         } else {
+          let started = Instant::now();
           if headless {
             self.inner.scrape().await;
           } else {
             self.inner.scrape_raw().await;
           }
+          crawl_limits.finish(&self.inner, started);
+          crawl_stats.flush().await;
+          changes.finish(&self.inner);
+          crawl_stats.finish();
         }
       }
     }
+
+    if !background {
+      // wait for the pages rendered as PDF.
+      self.page_hooks.pdf.finish().await;
+    }
+    self.restore_chrome_settings();
+
+    Ok(())
   }
 
   /// run a cron job
@@ -548,21 +1041,18 @@ impl Website {
   pub async unsafe fn run_cron(
     &mut self,
     on_page_event: Option<napi::threadsafe_function::ThreadsafeFunction<NPage>>,
-  ) -> Cron {
+  ) -> napi::Result<Cron> {
+    let browser_lease = self.setup_crawl(on_page_event.as_ref(), true).await?;
+
     let cron_handle = match on_page_event {
       Some(callback) => {
-        let mut rx2 = self
-          .inner
-          .subscribe(*BUFFER / 2)
-          .expect("sync feature should be enabled");
+        let mut rx2 = self.inner.subscribe(*BUFFER / 2);
         let raw_content = self.raw_content;
+        let page_hooks = self.page_hooks.clone();
 
         let handler = spider::tokio::spawn(async move {
           while let Ok(res) = rx2.recv().await {
-            callback.call(
-              Ok(NPage::new(&res, raw_content)),
-              napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
-            );
+            page_hooks.send(&callback, &res, raw_content).await;
           }
         });
 
@@ -571,9 +1061,30 @@ impl Website {
       _ => None,
     };
 
-    let inner = self.inner.run_cron().await;
+    // the client is setup again and the changes are compared for every run.
+    let inner = if self.client_layers.is_enabled() || self.page_hooks.changes.options.is_enabled() {
+      spider::async_job::Runner::new()
+        .add(Box::new(CronJob {
+          website: self.inner.clone(),
+          layers: self.client_layers.clone(),
+          changes: self.page_hooks.changes.clone(),
+          crawl_stats: self.crawl_stats.clone(),
+        }))
+        .run()
+        .await
+    } else {
+      self.inner.run_cron().await
+    };
+
+    self.restore_chrome_settings();
 
-    Cron { inner, cron_handle }
+    Ok(Cron {
+      inner,
+      cron_handle,
+      crawl_stats: self.crawl_stats.clone(),
+      pdf: self.page_hooks.pdf.clone(),
+      browser_lease,
+    })
   }
 
   #[napi]
@@ -594,24 +1105,148 @@ impl Website {
     self.inner.size() as u32
   }
 
+  #[napi]
+  /// get the live crawl statistics. This value updates for crawls running in the background.
+  pub fn stats(&self) -> NCrawlStats {
+    self.crawl_stats.snapshot(if self.running_in_background {
+      0
+    } else {
+      self.inner.size()
+    })
+  }
+
   /// get all the pages of a website - requires calling website.scrape
   #[napi]
   pub fn get_pages(&self) -> Vec<NPage> {
     let mut pages: Vec<NPage> = Vec::new();
     let raw_content = self.raw_content;
 
-    match self.inner.get_pages() {
-      Some(p) => {
-        for page in p.iter() {
-          pages.push(NPage::new(page, raw_content));
-        }
+    if let Some(p) = self.inner.get_pages() {
+      for page in p.iter() {
+        pages.push(self.page_hooks.new_page(page, raw_content));
       }
-      _ => (),
     }
 
     pages
   }
 
+  #[napi]
+  /// get the pages that failed during the last crawl. The errors are cleared with `website.clear`.
+  pub fn get_errors(&self) -> Vec<NCrawlError> {
+    self.crawl_stats.errors()
+  }
+
+  #[napi]
+  /// get the urls found during the last crawl that were not crawled with the rule that excluded them: external, blacklist, whitelist, robots, depth or budget. Requires `website.withSkipReport`.
+  pub fn get_skipped(&self) -> Vec<NSkippedUrl> {
+    self.skip_report.skipped(&self.inner)
+  }
+
+  #[napi]
+  /// get the near-duplicate pages found during the last crawl. Requires `website.withDedupe`.
+  pub fn get_duplicates(&self) -> Vec<NDuplicatePage> {
+    self.page_hooks.dedupe.duplicates()
+  }
+
+  #[napi]
+  /// get the urls of the previous run that were not found in the last run. Requires `website.withChangeTracking`.
+  pub fn get_removed(&self) -> Vec<String> {
+    self.page_hooks.changes.removed()
+  }
+
+  #[napi]
+  /// get the cookies imported and received during the crawl.
+  pub fn get_cookies(&self) -> Vec<NCookie> {
+    self.cookies.get_all()
+  }
+
+  #[napi]
+  /// import cookies using the `netscape` cookies.txt or `json` format. Defaults to `netscape`. Returns the amount of cookies imported.
+  pub fn import_cookies(&mut self, data: String, format: Option<String>) -> napi::Result<u32> {
+    let cookies = match format.as_deref().unwrap_or("netscape") {
+      "json" => serde_json::from_str::<Vec<NCookie>>(&data)?,
+      "netscape" => crate::cookies::parse_netscape(&data),
+      f => {
+        return Err(napi::Error::from_reason(format!(
+          "unknown cookie format {f}, use netscape or json"
+        )))
+      }
+    };
+    let size = cookies.len() as u32;
+
+    for cookie in cookies {
+      self.add_cookie(cookie);
+    }
+
+    Ok(size)
+  }
+
+  #[napi]
+  /// export the cookies using the `netscape` cookies.txt or `json` format. Defaults to `netscape`.
+  pub fn export_cookies(&self, format: Option<String>) -> napi::Result<String> {
+    let cookies = self.cookies.get_all();
+
+    match format.as_deref().unwrap_or("netscape") {
+      "json" => Ok(serde_json::to_string_pretty(&cookies)?),
+      "netscape" => Ok(crate::cookies::to_netscape(&cookies)),
+      f => Err(napi::Error::from_reason(format!(
+        "unknown cookie format {f}, use netscape or json"
+      ))),
+    }
+  }
+
+  #[napi]
+  /// get the crawl metrics using the Prometheus text format. The metrics are collected across all runs of the website.
+  pub fn metrics_text(&self) -> String {
+    self
+      .crawl_stats
+      .metrics
+      .render(self.inner.get_url().inner())
+  }
+
+  #[napi]
+  /// serve the crawl metrics using the Prometheus text format over HTTP. Use port 0 to bind to any open port. Returns the port bound.
+  pub async unsafe fn serve_metrics(
+    &mut self,
+    port: Option<u32>,
+    host: Option<String>,
+  ) -> napi::Result<u32> {
+    let listener = napi::tokio::net::TcpListener::bind(format!(
+      "{}:{}",
+      host.unwrap_or_else(|| "127.0.0.1".into()),
+      port.unwrap_or(9090)
+    ))
+    .await?;
+    let port = listener.local_addr()?.port();
+
+    if let Some(handle) = self.metrics_handle.take() {
+      handle.abort();
+    }
+
+    // the metrics are only collected from the crawl subscriptions.
+    self.ensure_stats_listener();
+
+    self.metrics_handle = Some(spider::tokio::spawn(crate::metrics::serve(
+      listener,
+      self.crawl_stats.clone(),
+      self.inner.get_url().inner().to_string(),
+    )));
+
+    Ok(port.into())
+  }
+
+  #[napi]
+  /// stop serving the crawl metrics.
+  pub fn stop_metrics(&mut self) -> bool {
+    match self.metrics_handle.take() {
+      Some(handle) => {
+        handle.abort();
+        true
+      }
+      _ => false,
+    }
+  }
+
   #[napi]
   /// drain all links from storing
   pub fn drain_links(&mut self) -> Vec<String> {
@@ -629,6 +1264,10 @@ impl Website {
   /// clear all links and page data
   pub fn clear(&mut self) {
     self.inner.clear();
+    self.crawl_limits.reset();
+    self.crawl_stats.clear();
+    self.skip_report.clear();
+    self.page_hooks.dedupe.clear();
   }
 
   #[napi]
@@ -644,21 +1283,15 @@ impl Website {
         for key in keys.into_iter() {
           let header_key = spider::reqwest::header::HeaderName::from_str(&key);
 
-          match header_key {
-            Ok(hn) => {
-              let header_value = obj
-                .get::<String, String>(key)
-                .unwrap_or_default()
-                .unwrap_or_default();
+          if let Ok(hn) = header_key {
+            let header_value = obj
+              .get::<String, String>(key)
+              .unwrap_or_default()
+              .unwrap_or_default();
 
-              match spider::reqwest::header::HeaderValue::from_str(&header_value) {
-                Ok(hk) => {
-                  h.append(hn, hk);
-                }
-                _ => (),
-              }
+            if let Ok(hk) = spider::reqwest::header::HeaderValue::from_str(&header_value) {
+              h.append(hn, hk);
             }
-            _ => (),
           }
         }
         self.inner.with_headers(Some(h));
@@ -671,6 +1304,80 @@ impl Website {
     self
   }
 
+  /// Set the cookies to use for the request. A string is used as the Cookie header for the website url.
+  #[napi]
+  pub fn with_cookies(&mut self, cookies: napi::Either<String, Vec<NCookie>>) -> &Self {
+    match cookies {
+      napi::Either::A(cookie_str) => {
+        let domain = self
+          .inner
+          .get_url_parsed()
+          .as_ref()
+          .and_then(|url| url.host_str().map(String::from))
+          .unwrap_or_default();
+
+        for (name, value) in cookie_str
+          .split(';')
+          .filter_map(|pair| pair.trim().split_once('='))
+        {
+          self.cookies.insert(NCookie {
+            name: name.into(),
+            value: value.into(),
+            domain: domain.clone(),
+            ..Default::default()
+          });
+        }
+
+        self.inner.configuration.with_cookies(&cookie_str);
+      }
+      napi::Either::B(cookies) => {
+        for cookie in cookies {
+          self.add_cookie(cookie);
+        }
+      }
+    }
+    self
+  }
+
+  /// Login before the crawl starts using a HTTP form or headless chrome. The session cookies are used for the crawl and the login runs again when a page redirects back to the login url. The page is then fetched again with the new session and its links are queued.
+  #[napi]
+  pub fn with_login(&mut self, login: Option<NLoginConfig>) -> &Self {
+    self.login.config.set(login);
+    self
+  }
+
+  /// Set the HTTP Basic or Digest credentials by origin. The credentials are only sent to the matching origin and never to other domains.
+  #[napi]
+  pub fn with_auth(&mut self, auth: Option<std::collections::HashMap<String, NAuth>>) -> &Self {
+    self.client_layers.auth.set_credentials(auth);
+    self
+  }
+
+  /// Set an async function returning the headers to use for the HTTP requests to the origin of the website. The headers are never sent to other domains. The function is called when the crawl starts and whenever a page returns 401 to refresh expired tokens.
+  #[napi]
+  pub fn with_auth_provider(
+    &mut self,
+    env: Env,
+    provider: Option<
+      napi::threadsafe_function::ThreadsafeFunction<
+        (),
+        napi::threadsafe_function::ErrorStrategy::Fatal,
+      >,
+    >,
+  ) -> &Self {
+    let provider = match provider {
+      Some(mut provider) => {
+        // the provider should not keep the process alive.
+        let _ = provider.unref(&env);
+        Some(provider)
+      }
+      _ => None,
+    };
+
+    self.client_layers.auth_provider.set_callback(provider);
+    self
+  }
+
   /// Add user agent to request.
   #[napi]
   pub fn with_user_agent(&mut self, user_agent: Option<&str>) -> &Self {
@@ -678,6 +1385,22 @@ impl Website {
     self
   }
 
+  /// get the robots.txt rules, sitemaps and crawl delays of the website host. The rules are checked for the user agent of the website by default.
+  #[napi]
+  pub async fn get_robots(&self) -> Robots {
+    Robots::fetch(
+      &self.http_client(),
+      self.inner.get_url().as_ref(),
+      self
+        .inner
+        .configuration
+        .user_agent
+        .as_ref()
+        .map(|user_agent| user_agent.to_string()),
+    )
+    .await
+  }
+
   /// Respect robots.txt file.
   #[napi]
   pub fn with_respect_robots_txt(&mut self, respect_robots_txt: bool) -> &Self {
@@ -703,7 +1426,48 @@ impl Website {
 
     intercept_config.block_visuals = block_images;
 
-    self.inner.with_chrome_intercept(intercept_config);
+    self
+      .page_hooks
+      .blocking
+      .set_chrome_intercept(&mut self.inner, intercept_config);
+    self
+  }
+
+  /// Block the resources in headless mode by type, url pattern and domain. The page blockedResources field counts the resources referenced by the page html matching the rules. This method does nothing if the `chrome` is not enabled.
+  #[napi]
+  pub fn with_resource_blocking(&mut self, blocking: Option<NResourceBlocking>) -> &Self {
+    self
+      .page_hooks
+      .blocking
+      .set_options(&mut self.inner, blocking);
+    self
+  }
+
+  /// Normalize the links before they enter the visited set removing the fragments. The page originalUrl and normalizedUrl fields are set and pages with a normalized url already sent are skipped.
+  #[napi]
+  pub fn with_url_normalization(&mut self, normalization: Option<NUrlNormalization>) -> &Self {
+    self.page_hooks.normalizer.options.set(normalization);
+    self
+  }
+
+  /// Skip the page events of the near-duplicate pages using the SimHash of the page text. The page fingerprint field is set and the duplicates are counted in the stats.
+  #[napi]
+  pub fn with_dedupe(&mut self, dedupe: Option<NDedupe>) -> &Self {
+    self.page_hooks.dedupe.options.set(dedupe);
+    self
+  }
+
+  /// Compare the content hash of the pages by url with the last run. The page changeStatus field is set and the pages of the last run not found are sent as removed at the end of the run. Set the path to keep the hashes in a JSON file between processes.
+  #[napi]
+  pub fn with_change_tracking(&mut self, change_tracking: Option<NChangeTracking>) -> &Self {
+    self.page_hooks.changes.options.set(change_tracking);
+    self
+  }
+
+  /// Use the chrome instances of the pool for headless crawls. The pool can be shared by many websites and a crawl waits for a free chrome instance. The crawl concurrency is limited to the tabs allowed per chrome instance and the website settings are restored after the crawl.
+  #[napi]
+  pub fn with_browser_pool(&mut self, browser_pool: &BrowserPool) -> &Self {
+    self.browser_pool = Some(browser_pool.pool());
     self
   }
 
@@ -757,30 +1521,83 @@ impl Website {
     self
       .inner
       .configuration
-      .with_request_timeout(match request_timeout {
-        Some(d) => Some(Duration::from_millis(d.into())),
-        _ => None,
-      });
+      .with_request_timeout(request_timeout.map(|d| Duration::from_millis(d.into())));
+    self
+  }
+
+  /// Max time to wait for the entire crawl in milliseconds. The crawl stops cleanly and the status is set to `TimedOut`.
+  #[napi]
+  pub fn with_crawl_timeout(&mut self, crawl_timeout: Option<u32>) -> &Self {
+    let crawl_timeout = crawl_timeout.map(|d| Duration::from_millis(d.into()));
+    self.inner.configuration.with_crawl_timeout(crawl_timeout);
+    self.crawl_limits.timeout = crawl_timeout;
+    self
+  }
+
+  /// Set the max amount of pages to crawl. The crawl stops cleanly and the status is set to `LimitReached` when pages were left. The limit caps the `*` budget without changing the budget set. If the value is 0 there is no limit.
+  #[napi]
+  pub fn with_limit(&mut self, limit: u32) -> &Self {
+    self.crawl_limits.limit = limit;
+    self.apply_limit();
     self
   }
 
   /// add external domains
   #[napi]
   pub fn with_external_domains(&mut self, external_domains: Option<Vec<String>>) -> &Self {
-    self.inner.with_external_domains(match external_domains {
-      Some(ext) => Some(ext.into_iter()),
-      _ => None,
-    });
     self
+      .inner
+      .with_external_domains(external_domains.map(|ext| ext.into_iter()));
+    self
+  }
+
+  /// Set the browser viewport. This does nothing without chrome.
+  #[napi]
+  pub fn with_viewport(&mut self, viewport: Option<NViewport>) -> &Self {
+    self
+      .inner
+      .configuration
+      .with_viewport(viewport.map(Into::into));
+    self
+  }
+
+  /// Set the browser timezone using the IANA name like `America/New_York`. This does nothing without chrome.
+  #[napi]
+  pub fn with_timezone(&mut self, timezone: Option<String>) -> &Self {
+    self.inner.configuration.with_timezone_id(timezone);
+    self
+  }
+
+  /// Set the browser locale like `fr-FR`. This does nothing without chrome.
+  #[napi]
+  pub fn with_locale(&mut self, locale: Option<String>) -> &Self {
+    self.inner.configuration.with_locale(locale);
+    self
+  }
+
+  /// Emulate a device preset setting the viewport and user agent: desktop, laptop, iphone-15, iphone-se, ipad, pixel-7 or galaxy-s23. This does nothing without chrome.
+  #[napi]
+  pub fn with_device(&mut self, device: String) -> napi::Result<&Self> {
+    match device_preset(&device) {
+      Some((viewport, user_agent)) => {
+        self
+          .inner
+          .configuration
+          .with_viewport(Some(viewport.into()));
+        self.inner.configuration.with_user_agent(Some(user_agent));
+        Ok(self)
+      }
+      _ => Err(napi::Error::from_reason(format!(
+        "unknown device {device}, expected one of {}",
+        device_names().join(", ")
+      ))),
+    }
   }
 
   /// Use stealth mode for the request. This does nothing without chrome.
   #[napi]
   pub fn with_stealth(&mut self, stealth_mode: Option<bool>) -> &Self {
-    self.inner.with_stealth(match stealth_mode {
-      Some(ext) => ext,
-      _ => false,
-    });
+    self.inner.with_stealth(stealth_mode.unwrap_or_default());
     self
   }
 
@@ -789,27 +1606,16 @@ impl Website {
   pub fn with_danger_accept_invalid_certs(&mut self, accept_invalid_certs: Option<bool>) -> &Self {
     self
       .inner
-      .with_danger_accept_invalid_certs(match accept_invalid_certs {
-        Some(ext) => ext,
-        _ => false,
-      });
+      .with_danger_accept_invalid_certs(accept_invalid_certs.unwrap_or_default());
     self
   }
 
   #[napi]
   /// Set the crawling budget
   pub fn with_budget(&mut self, budget: Option<std::collections::HashMap<String, u32>>) -> &Self {
-    use spider::hashbrown::hash_map::HashMap;
-
-    match budget {
-      Some(d) => {
-        self.inner.with_budget(Some(
-          d.iter()
-            .map(|(k, v)| (k.as_str(), *v))
-            .collect::<HashMap<&str, u32>>(),
-        ));
-      }
-      _ => (),
+    if let Some(d) = budget {
+      self.crawl_limits.budget = Some(d);
+      self.apply_limit();
     }
 
     self
@@ -919,6 +1725,33 @@ impl Website {
     self
   }
 
+  #[napi]
+  /// Run the automation steps after navigation on the pages matching the url or path pattern in headless mode. This method does nothing if the `chrome` feature is not enabled.
+  pub fn with_automation_scripts(
+    &mut self,
+    scripts: Option<std::collections::HashMap<String, Vec<NAutomationStep>>>,
+  ) -> &Self {
+    self
+      .inner
+      .with_automation_scripts(scripts.clone().map(to_automation_scripts));
+    self.automation_scripts = scripts;
+    self
+  }
+
+  #[napi]
+  /// Evaluate the javascript expression after page load on the pages matching the url or path pattern in headless mode. Use `/` for every page. The JSON result is set on the page evaluated field. This method does nothing if the `chrome` feature is not enabled.
+  pub fn with_evaluate_scripts(
+    &mut self,
+    scripts: Option<std::collections::HashMap<String, String>>,
+  ) -> &Self {
+    self
+      .inner
+      .with_execution_scripts(scripts.clone().map(to_execution_scripts));
+    self.page_hooks.set_evaluate(scripts.is_some());
+    self.evaluate_scripts = scripts;
+    self
+  }
+
   #[napi]
   /// Wait for idle network request. This method does nothing if the `chrome` feature is not enabled.
   pub fn with_wait_for_idle_network(&mut self, seconds: Option<u32>, nanos: Option<u32>) -> &Self {
@@ -958,10 +1791,7 @@ impl Website {
     use serde_json::Value;
     use spider::configuration::GPTConfigs;
     let openai_configs: Option<Value> = match openai_configs {
-      Some(obj) => match env.from_js_value(obj) {
-        Ok(e) => Some(e),
-        _ => None,
-      },
+      Some(obj) => env.from_js_value(obj).ok(),
       None => None,
     };
 
@@ -1022,10 +1852,7 @@ impl Website {
     use serde_json::Value;
     use spider::configuration::ScreenShotConfig;
     let screenshot_configs: Option<Value> = match screenshot_configs {
-      Some(obj) => match env.from_js_value(obj) {
-        Ok(e) => Some(e),
-        _ => None,
-      },
+      Some(obj) => env.from_js_value(obj).ok(),
       None => None,
     };
 
@@ -1039,6 +1866,13 @@ impl Website {
     self
   }
 
+  /// Render the pages of the headless crawls as PDF using the chrome of the crawl. The bytes are set on the page pdf field.
+  #[napi]
+  pub fn with_pdf(&mut self, options: Option<NPdfOptions>) -> &Self {
+    self.page_hooks.pdf.options.set(options);
+    self
+  }
+
   /// Delay between request as ms.
   #[napi]
   pub fn with_delay(&mut self, delay: u32) -> &Self {
@@ -1053,13 +1887,27 @@ impl Website {
     self
   }
 
-  /// Return the links found on the page in the channel subscriptions. This method does nothing if the `decentralized` is enabled.
+  /// Set the amount of retries for the failed requests. The value is capped at 255.
   #[napi]
-  pub fn with_return_page_links(&mut self, return_page_links: bool) -> &Self {
+  pub fn with_retry(&mut self, retry: u32) -> &Self {
     self
       .inner
       .configuration
-      .with_return_page_links(return_page_links);
+      .with_retry(u8::try_from(retry).unwrap_or(u8::MAX));
+    self
+  }
+
+  /// Return the links found on the page in the channel subscriptions. This method does nothing if the `decentralized` is enabled.
+  #[napi]
+  pub fn with_return_page_links(&mut self, return_page_links: bool) -> &Self {
+    self.page_hooks.set_return_page_links(return_page_links);
+    self
+  }
+
+  /// Report the urls found that were not crawled with the rule that excluded them using `website.getSkipped`. The links are collected during the crawl without being returned on the pages.
+  #[napi]
+  pub fn with_skip_report(&mut self, skip_report: bool) -> &Self {
+    self.skip_report.set_enabled(skip_report);
     self
   }
 
@@ -1074,27 +1922,551 @@ impl Website {
   #[napi]
   pub fn with_sitemap(&mut self, sitemap: Option<&str>) -> &Self {
     self.inner.configuration.with_sitemap(sitemap);
+    self.sitemap_url = sitemap.map(String::from);
     self
   }
 
-  /// Use proxies for request.
+  #[napi]
+  /// get the entries of the sitemaps following the sitemap indexes and gzip sitemaps. The sitemap url set with `website.withSitemap` is used first, then the sitemaps listed in robots.txt and /sitemap.xml.
+  pub async fn get_sitemap_entries(&self) -> Vec<NSitemapEntry> {
+    let client = self.http_client();
+    let sitemaps = sitemap_urls(
+      &client,
+      self.inner.get_url().as_ref(),
+      self.sitemap_url.as_deref(),
+    )
+    .await;
+
+    fetch_entries(&client, sitemaps).await
+  }
+
+  /// Use proxies for request. With 2 or more proxies and the auth, request limits or rotation the proxies are rotated by the HTTP client.
   #[napi]
   pub fn with_proxies(&mut self, proxies: Option<Vec<String>>) -> &Self {
+    self.client_layers.proxies.set_proxies(proxies.clone());
     self.inner.configuration.with_proxies(proxies);
     self
   }
 
+  /// Choose the proxy for every request using a strategy. Proxies failing repeatedly are ejected for a while. The rotation applies to the HTTP requests.
+  #[napi]
+  pub fn with_proxy_rotation(&mut self, rotation: Option<NProxyRotation>) -> &Self {
+    self.client_layers.proxies.options.set(rotation);
+    self
+  }
+
+  #[napi]
+  /// get the health of every proxy used by the rotation during the last crawl.
+  pub fn get_proxy_stats(&self) -> Vec<NProxyStats> {
+    self.client_layers.proxies.stats()
+  }
+
+  /// Send the live crawl statistics to the callback on an interval in milliseconds. Defaults to 1000ms.
+  #[napi]
+  pub fn with_on_progress(
+    &mut self,
+    env: Env,
+    mut on_progress: napi::threadsafe_function::ThreadsafeFunction<NCrawlStats>,
+    interval: Option<u32>,
+  ) -> &Self {
+    // the callback should not keep the process alive after the crawl.
+    let _ = on_progress.unref(&env);
+    self.on_progress = Some((
+      on_progress,
+      Duration::from_millis(interval.unwrap_or(1000).max(1).into()),
+    ));
+    self
+  }
+
+  /// subscribe to the pages to collect the statistics.
+  fn ensure_stats_listener(&mut self) {
+    // background crawls clone the website sharing the same channel.
+    if self
+      .stats_handle
+      .as_ref()
+      .is_none_or(|handle| handle.is_finished())
+    {
+      let mut rx2 = self.inner.subscribe(*BUFFER / 2);
+      let crawl_stats = self.crawl_stats.clone();
+      let cookies = self.cookies.clone();
+      let skip_report = self.skip_report.clone();
+      let page_hooks = self.page_hooks.clone();
+      let login = self.login.clone();
+      let cookie_jar = self.inner.cookie_jar.clone();
+      // the pages fetched again after the login are sent to the subscriptions with their links queued.
+      let pages = self
+        .inner
+        .get_channel()
+        .as_ref()
+        .map(|channel| channel.0.clone());
+      let queue = self.inner.queue(*BUFFER);
+      let (flush, mut flush_rx) = spider::tokio::sync::mpsc::unbounded_channel::<FlushRequest>();
+
+      self.crawl_stats.set_flush(flush);
+
+      let record = move |res: spider::page::Page| {
+        crawl_stats.record(&res);
+        cookies.record(&res);
+        skip_report.record(&res);
+
+        if page_hooks.is_duplicate(&res) {
+          crawl_stats.record_duplicate();
+        }
+
+        page_hooks.changes.status(
+          res.get_url(),
+          res.status_code.as_u16(),
+          res.get_html_bytes_u8(),
+        );
+
+        // the session expired, login again and fetch the page again.
+        if login.is_login_redirect(&res) {
+          let login = login.clone();
+          let cookies = cookies.clone();
+          let cookie_jar = cookie_jar.clone();
+          let pages = pages.clone();
+          let queue = queue.clone();
+          let url = res.get_url().to_string();
+
+          spider::tokio::spawn(async move {
+            if let Err(e) = login.run(&cookie_jar, &cookies).await {
+              log::warn!("{e}");
+              return;
+            }
+
+            if let Some((page, links)) = login.refetch(&url).await {
+              if let Some(queue) = queue {
+                for link in links {
+                  let _ = queue.send(link);
+                }
+              }
+              if let Some(pages) = pages {
+                let _ = pages.send(page);
+              }
+            }
+          });
+        }
+      };
+
+      self.stats_handle = Some(spider::tokio::spawn(async move {
+        use spider::tokio::sync::broadcast::error::{RecvError, TryRecvError};
+        loop {
+          spider::tokio::select! {
+            res = rx2.recv() => match res {
+              Ok(res) => record(res),
+              Err(RecvError::Lagged(_)) => continue,
+              _ => break,
+            },
+            Some(done) = flush_rx.recv() => {
+              // the pages sent before the flush are already queued.
+              loop {
+                match rx2.try_recv() {
+                  Ok(res) => record(res),
+                  Err(TryRecvError::Lagged(_)) => continue,
+                  _ => break,
+                }
+              }
+              let _ = done.send(());
+            }
+          }
+        }
+      }));
+    }
+  }
+
+  /// setup the state shared by all crawls before a new run. The chrome settings are restored when the setup fails.
+  async fn setup_crawl(
+    &mut self,
+    on_page_event: Option<&napi::threadsafe_function::ThreadsafeFunction<NPage>>,
+    headless: bool,
+  ) -> napi::Result<Option<BrowserLease>> {
+    self.restore_chrome_settings();
+    self.chrome_settings = Some(ChromeSettings::save(&self.inner));
+
+    let setup = self.setup_run(on_page_event, headless).await;
+
+    if setup.is_err() {
+      self.restore_chrome_settings();
+    }
+
+    setup
+  }
+
+  /// setup the state of the run leasing the chrome instance for the headless crawls.
+  async fn setup_run(
+    &mut self,
+    on_page_event: Option<&napi::threadsafe_function::ThreadsafeFunction<NPage>>,
+    headless: bool,
+  ) -> napi::Result<Option<BrowserLease>> {
+    self.crawl_limits.reset();
+    self.start_stats();
+    self.page_hooks.changes.set_callback(on_page_event.cloned());
+    self.page_hooks.changes.start();
+
+    // the chrome instances of the pool are only used by the headless crawls.
+    let browser_lease = match self.browser_pool {
+      Some(ref pool) if headless => {
+        let lease = pool
+          .acquire(self.crawl_stats.clone())
+          .await
+          .map_err(napi::Error::from_reason)?;
+        lease.apply(&mut self.inner);
+        Some(lease)
+      }
+      _ => None,
+    };
+
+    self
+      .client_layers
+      .setup(&mut self.inner)
+      .await
+      .map_err(napi::Error::from_reason)?;
+
+    self
+      .page_hooks
+      .pdf
+      .prepare(&mut self.inner, headless)
+      .await
+      .map_err(napi::Error::from_reason)?;
+    let limit = self.crawl_limits.limit > 0;
+
+    // the links are collected for the skip report and the page limit status.
+    self.inner.configuration.with_return_page_links(
+      self.page_hooks.return_page_links() || self.skip_report.is_enabled() || limit,
+    );
+    self.skip_report.prepare(limit);
+
+    // the urls blocked by robots.txt are reported as skipped and as errors.
+    let skip_report = self.skip_report.clone();
+    let crawl_stats = self.crawl_stats.clone();
+
+    self
+      .inner
+      .with_on_link_blocked_callback(Some(move |url: String| {
+        skip_report.record_blocked(url.clone());
+        crawl_stats.record_error(NCrawlError::robots(url));
+      }));
+    self.page_hooks.normalizer.prepare(&mut self.inner);
+    self.page_hooks.dedupe.clear();
+
+    if self.login.config.is_enabled() {
+      self.login.prepare(&self.inner);
+      self
+        .login
+        .run(&self.inner.cookie_jar, &self.cookies)
+        .await
+        .map_err(napi::Error::from_reason)?;
+    }
+
+    Ok(browser_lease)
+  }
+
+  /// restore the chrome settings replaced for the last crawl.
+  fn restore_chrome_settings(&mut self) {
+    if let Some(settings) = self.chrome_settings.take() {
+      settings.restore(&mut self.inner);
+    }
+  }
+
+  /// the HTTP client of the crawl or a client from the website configuration.
+  fn http_client(&self) -> spider::Client {
+    // the spider connector runs the connections on a runtime started by the crawls.
+    spider::utils::connect::init_background_runtime();
+
+    match self.inner.get_client() {
+      Some(client) => client.clone(),
+      _ => self.inner.configure_http_client_builder().build(),
+    }
+  }
+
+  /// share the request limits of a crawl pool capping the crawl concurrency to the domain limit.
+  pub(crate) fn with_request_limits(&mut self, limits: Arc<RequestLimits>, per_domain: u32) {
+    if per_domain > 0 {
+      let concurrency_limit = self.inner.configuration.concurrency_limit;
+      self.inner.configuration.with_concurrency_limit(Some(
+        concurrency_limit.map_or(per_domain as usize, |limit| limit.min(per_domain as usize)),
+      ));
+    }
+    self.client_layers.limits = limits;
+  }
+
+  /// the result of the last crawl for a crawl pool.
+  pub(crate) fn site_result(&self, error: Option<String>) -> NSiteResult {
+    NSiteResult {
+      url: self.inner.get_url().to_string(),
+      status: self.status(),
+      stats: self.crawl_stats.snapshot(self.inner.get_links().len()),
+      error,
+    }
+  }
+
+  /// build the website applying the config options.
+  fn from_website_config(config: NWebsiteConfig) -> napi::Result<Self> {
+    let mut website = Website::new(config.url, config.raw_content);
+
+    // the viewport and user agent options take priority over the device.
+    if let Some(device) = config.device {
+      website.with_device(device)?;
+    }
+    if let Some(headers) = config.headers {
+      let headers = to_header_map(headers).map_err(napi::Error::from_reason)?;
+      website.inner.with_headers(Some(headers));
+    }
+    if let Some(cookies) = config.cookies {
+      let cookies =
+        match cookies {
+          serde_json::Value::String(cookie_str) => napi::Either::A(cookie_str),
+          cookies => napi::Either::B(serde_json::from_value::<Vec<NCookie>>(cookies).map_err(
+            |e| napi::Error::from_reason(format!("invalid website config cookies: {e}")),
+          )?),
+        };
+      website.with_cookies(cookies);
+    }
+    if config.login.is_some() {
+      website.with_login(config.login);
+    }
+    if config.auth.is_some() {
+      website.with_auth(config.auth);
+    }
+    if config.user_agent.is_some() {
+      website.with_user_agent(config.user_agent.as_deref());
+    }
+    if let Some(respect_robots_txt) = config.respect_robots_txt {
+      website.with_respect_robots_txt(respect_robots_txt);
+    }
+    if let Some(full_resources) = config.full_resources {
+      website.with_full_resources(full_resources);
+    }
+    if let Some(intercept) = config.chrome_intercept {
+      website.with_chrome_intercept(
+        intercept.enabled,
+        intercept.block_images.unwrap_or_default(),
+      );
+    }
+    if config.resource_blocking.is_some() {
+      website.with_resource_blocking(config.resource_blocking);
+    }
+    if config.url_normalization.is_some() {
+      website.with_url_normalization(config.url_normalization);
+    }
+    if config.dedupe.is_some() {
+      website.with_dedupe(config.dedupe);
+    }
+    if config.change_tracking.is_some() {
+      website.with_change_tracking(config.change_tracking);
+    }
+    if let Some(chrome_connection) = config.chrome_connection {
+      website.with_chrome_connection(chrome_connection);
+    }
+    if let Some(preserve_host) = config.preserve_host_header {
+      website.with_preserve_host_header(preserve_host);
+    }
+    if let Some(subdomains) = config.subdomains {
+      website.with_subdomains(subdomains);
+    }
+    if let Some(tld) = config.tld {
+      website.with_tld(tld);
+    }
+    if let Some(http2_prior_knowledge) = config.http2_prior_knowledge {
+      website.with_http2_prior_knowledge(http2_prior_knowledge);
+    }
+    if config.request_timeout.is_some() {
+      website.with_request_timeout(config.request_timeout);
+    }
+    if config.crawl_timeout.is_some() {
+      website.with_crawl_timeout(config.crawl_timeout);
+    }
+    if config.budget.is_some() {
+      website.with_budget(config.budget);
+    }
+    if let Some(limit) = config.limit {
+      website.with_limit(limit);
+    }
+    if config.external_domains.is_some() {
+      website.with_external_domains(config.external_domains);
+    }
+    if config.viewport.is_some() {
+      website.with_viewport(config.viewport);
+    }
+    if config.timezone.is_some() {
+      website.with_timezone(config.timezone);
+    }
+    if config.locale.is_some() {
+      website.with_locale(config.locale);
+    }
+    if config.stealth.is_some() {
+      website.with_stealth(config.stealth);
+    }
+    if config.danger_accept_invalid_certs.is_some() {
+      website.with_danger_accept_invalid_certs(config.danger_accept_invalid_certs);
+    }
+    if let Some(redirect_limit) = config.redirect_limit {
+      website.with_redirect_limit(redirect_limit);
+    }
+    if let Some(redirect_policy) = config.redirect_policy {
+      match redirect_policy.as_str() {
+        "strict" => website.with_redirect_policy(true),
+        "loose" => website.with_redirect_policy(false),
+        _ => {
+          return Err(napi::Error::from_reason(format!(
+            "invalid website config redirectPolicy {redirect_policy}, expected strict or loose"
+          )))
+        }
+      };
+    }
+    if config.blacklist_url.is_some() {
+      website.with_blacklist_url(config.blacklist_url);
+    }
+    if config.whitelist_url.is_some() {
+      website.with_whitelist_url(config.whitelist_url);
+    }
+    if let Some(wait_for) = config.wait_for_delay {
+      website.with_wait_for_delay(wait_for.seconds, wait_for.nanos);
+    }
+    if let Some(wait_for) = config.wait_for_selector {
+      website.with_wait_for_selector(Some(&wait_for.selector), wait_for.seconds, wait_for.nanos);
+    }
+    if let Some(wait_for) = config.wait_for_idle_network {
+      website.with_wait_for_idle_network(wait_for.seconds, wait_for.nanos);
+    }
+    if config.automation_scripts.is_some() {
+      website.with_automation_scripts(config.automation_scripts);
+    }
+    if config.evaluate_scripts.is_some() {
+      website.with_evaluate_scripts(config.evaluate_scripts);
+    }
+    if let Some(cron) = config.cron {
+      match cron.cron_type.as_deref() {
+        None | Some("crawl") | Some("scrape") => website.with_cron(cron.expression, cron.cron_type),
+        Some(cron_type) => {
+          return Err(napi::Error::from_reason(format!(
+            "invalid website config cron type {cron_type}, expected crawl or scrape"
+          )))
+        }
+      };
+    }
+    if let Some(openai) = config.openai {
+      let configs: spider::configuration::GPTConfigs = serde_json::from_value(openai)
+        .map_err(|e| napi::Error::from_reason(format!("invalid website config openai: {e}")))?;
+      website.inner.with_openai(Some(configs));
+    }
+    if let Some(screenshot) = config.screenshot {
+      let configs: spider::configuration::ScreenShotConfig = serde_json::from_value(screenshot)
+        .map_err(|e| napi::Error::from_reason(format!("invalid website config screenshot: {e}")))?;
+      website.inner.with_screenshot(Some(configs));
+    }
+    if config.pdf.is_some() {
+      website.with_pdf(config.pdf);
+    }
+    if let Some(delay) = config.delay {
+      website.with_delay(delay);
+    }
+    if let Some(depth) = config.depth {
+      website.with_depth(depth);
+    }
+    if let Some(retry) = config.retry {
+      website.with_retry(retry);
+    }
+    if let Some(return_page_links) = config.return_page_links {
+      website.with_return_page_links(return_page_links);
+    }
+    if let Some(skip_report) = config.skip_report {
+      website.with_skip_report(skip_report);
+    }
+    if let Some(caching) = config.caching {
+      website.with_caching(caching);
+    }
+    if config.sitemap.is_some() {
+      website.with_sitemap(config.sitemap.as_deref());
+    }
+    if config.proxies.is_some() {
+      website.with_proxies(config.proxies);
+    }
+    if config.proxy_rotation.is_some() {
+      website.with_proxy_rotation(config.proxy_rotation);
+    }
+
+    Ok(website)
+  }
+
+  /// start collecting the statistics for a new run.
+  fn start_stats(&mut self) {
+    self.ensure_stats_listener();
+    self.crawl_stats.start(self.inner.configuration.retry);
+
+    // the progress of the last run stops sending.
+    if let Some(handle) = self.progress_handle.take() {
+      handle.abort();
+    }
+
+    if let Some((callback, interval)) = &self.on_progress {
+      let callback = callback.clone();
+      let interval = *interval;
+      let crawl_stats = self.crawl_stats.clone();
+
+      self.progress_handle = Some(spider::tokio::spawn(async move {
+        loop {
+          spider::tokio::time::sleep(interval).await;
+          let finished = crawl_stats.is_finished();
+          callback.call(
+            Ok(crawl_stats.snapshot(0)),
+            napi::threadsafe_function::ThreadsafeFunctionCallMode::NonBlocking,
+          );
+          if finished {
+            break;
+          }
+        }
+      }));
+    }
+  }
+
+  /// add a cookie to the jar used for the crawl.
+  fn add_cookie(&mut self, cookie: NCookie) {
+    if let Some(url) = cookie.url() {
+      self
+        .inner
+        .cookie_jar
+        .add_cookie_str(&cookie.to_set_cookie(), &url);
+    }
+    self.cookies.insert(cookie);
+  }
+
+  /// apply the page limit on top of the crawl budget keeping the lowest budget for all of the paths.
+  fn apply_limit(&mut self) {
+    let budget = limit_budget(&self.crawl_limits);
+
+    self.inner.with_budget(if budget.is_empty() {
+      None
+    } else {
+      Some(budget)
+    });
+  }
+
   #[napi]
   /// build the inner website - not required for all builder_steps
   pub fn build(&mut self) -> &Self {
-    match self.inner.build() {
-      Ok(w) => self.inner = w,
-      _ => (),
+    if let Ok(w) = self.inner.build() {
+      self.inner = w
     }
     self
   }
 }
 
+/// the crawl budget with the page limit capping the `*` budget.
+fn limit_budget(crawl_limits: &CrawlLimits) -> spider::hashbrown::HashMap<&str, u32> {
+  let mut budget: spider::hashbrown::HashMap<&str, u32> = match crawl_limits.budget {
+    Some(ref budget) => budget.iter().map(|(k, v)| (k.as_str(), *v)).collect(),
+    _ => Default::default(),
+  };
+
+  if crawl_limits.limit > 0 {
+    let all = budget.entry("*").or_insert(crawl_limits.limit);
+    *all = (*all).min(crawl_limits.limit);
+  }
+
+  budget
+}
+
 /// a runner for handling crons
 #[napi]
 pub struct Cron {
@@ -1102,6 +2474,12 @@ pub struct Cron {
   inner: spider::async_job::Runner,
   /// inner cron handle
   cron_handle: Option<JoinHandle<()>>,
+  /// the live crawl statistics.
+  crawl_stats: Arc<CrawlStats>,
+  /// the PDF renders of the cron runs.
+  pdf: Arc<PdfRenderer>,
+  /// the chrome instance of the pool used by the cron.
+  browser_lease: Option<BrowserLease>,
 }
 
 #[napi]
@@ -1110,9 +2488,11 @@ impl Cron {
   #[napi]
   pub async unsafe fn stop(&mut self) {
     self.inner.stop().await;
-    match &self.cron_handle {
-      Some(h) => h.abort(),
-      _ => (),
+    if let Some(h) = &self.cron_handle {
+      h.abort()
     }
+    self.crawl_stats.finish();
+    self.pdf.finish().await;
+    self.browser_lease = None;
   }
 }